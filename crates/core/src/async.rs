@@ -0,0 +1,182 @@
+//! Async wrappers around the blocking services
+//!
+//! Every call clones the underlying service and runs it on Tokio's blocking
+//! thread pool, so embedders running inside a Tokio runtime never block the
+//! reactor on SQLite.
+
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+
+use crate::db::Database;
+use crate::domain::{GamesService, StatisticsService, TimeTrackingService};
+use crate::error::{Error, Result};
+use crate::models::{DailyStatistics, Game, GameStatistics, PlaySession};
+
+async fn run_blocking<F, T>(f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| Error::Internal(format!("Blocking task failed: {}", e)))?
+}
+
+#[derive(Clone)]
+pub struct AsyncTimeTrackingService {
+    inner: TimeTrackingService,
+}
+
+impl AsyncTimeTrackingService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            inner: TimeTrackingService::new(db),
+        }
+    }
+
+    /// Add playtime for a game
+    pub async fn add_time(
+        &self,
+        game_id: String,
+        game_name: String,
+        started_at: f64,
+        ended_at: f64,
+        source: Option<String>,
+        idempotency_key: Option<String>,
+    ) -> Result<usize> {
+        let inner = self.inner.clone();
+
+        run_blocking(move || {
+            inner.add_time(
+                &game_id,
+                &game_name,
+                started_at,
+                ended_at,
+                source.as_deref(),
+                idempotency_key.as_deref(),
+            )
+        })
+        .await
+    }
+
+    /// Get all sessions for a game
+    pub async fn get_game_sessions(&self, game_id: String) -> Result<Vec<PlaySession>> {
+        let inner = self.inner.clone();
+
+        run_blocking(move || inner.get_game_sessions(&game_id)).await
+    }
+
+    /// Get total playtime for a game
+    pub async fn get_total_playtime(&self, game_id: String) -> Result<i64> {
+        let inner = self.inner.clone();
+
+        run_blocking(move || inner.get_total_playtime(&game_id)).await
+    }
+}
+
+#[derive(Clone)]
+pub struct AsyncStatisticsService {
+    inner: StatisticsService,
+}
+
+impl AsyncStatisticsService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            inner: StatisticsService::new(db),
+        }
+    }
+
+    /// Get overall statistics for all games
+    pub async fn get_overall(&self) -> Result<Vec<GameStatistics>> {
+        let inner = self.inner.clone();
+
+        run_blocking(move || inner.get_overall()).await
+    }
+
+    /// Get daily statistics for a date range
+    pub async fn get_daily(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<DailyStatistics>> {
+        let inner = self.inner.clone();
+
+        run_blocking(move || inner.get_daily(start_date, end_date)).await
+    }
+
+    /// Get statistics for a specific game
+    pub async fn get_for_game(&self, game_id: String) -> Result<Option<GameStatistics>> {
+        let inner = self.inner.clone();
+
+        run_blocking(move || inner.get_for_game(&game_id)).await
+    }
+}
+
+#[derive(Clone)]
+pub struct AsyncGamesService {
+    inner: GamesService,
+}
+
+impl AsyncGamesService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            inner: GamesService::new(db),
+        }
+    }
+
+    /// Get all games
+    pub async fn get_all(&self) -> Result<Vec<Game>> {
+        let inner = self.inner.clone();
+
+        run_blocking(move || inner.get_all()).await
+    }
+
+    /// Save a game in dictionary
+    pub async fn save(&self, game: Game) -> Result<()> {
+        let inner = self.inner.clone();
+
+        run_blocking(move || inner.save(&game)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use chrono::{Local, TimeZone};
+
+    use super::*;
+    use crate::db::migrations::run_migrations;
+
+    #[tokio::test]
+    async fn test_add_time_async() {
+        let db_path = env::temp_dir().join(format!("test_async_{}.db", uuid::Uuid::new_v4()));
+        let db = Database::new(&db_path).unwrap();
+        db.with_connection(run_migrations).unwrap();
+
+        let service = AsyncTimeTrackingService::new(Arc::new(db));
+        let noon = Local
+            .with_ymd_and_hms(2024, 3, 1, 12, 0, 0)
+            .unwrap()
+            .timestamp() as f64;
+
+        service
+            .add_time(
+                "123".into(),
+                "Test Game".into(),
+                noon,
+                noon + 600.0,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(service.get_total_playtime("123".into()).await.unwrap(), 600);
+        assert_eq!(
+            service.get_game_sessions("123".into()).await.unwrap().len(),
+            1
+        );
+    }
+}
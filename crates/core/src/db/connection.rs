@@ -2,9 +2,25 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use parking_lot::Mutex;
-use rusqlite::{Connection, OpenFlags};
+use rusqlite::backup::Progress;
+use rusqlite::{Connection, MAIN_DB, OpenFlags};
 
-use crate::Result;
+use crate::db::migrations;
+use crate::{Error, Result};
+
+/// Default SQLite page cache size in KiB (~20MB)
+pub const DEFAULT_CACHE_SIZE_KIB: i64 = 20_000;
+
+/// Bounds applied to a configured cache size, in KiB
+const MIN_CACHE_SIZE_KIB: i64 = 2_000;
+const MAX_CACHE_SIZE_KIB: i64 = 256_000;
+
+/// Frame counts reported by a WAL checkpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WalCheckpoint {
+    pub log_frames: i64,
+    pub checkpointed_frames: i64,
+}
 
 #[derive(Clone)]
 pub struct Database {
@@ -14,13 +30,20 @@ pub struct Database {
 
 impl Database {
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::with_cache_size(path, DEFAULT_CACHE_SIZE_KIB)
+    }
+
+    /// Open a database with a custom page cache size in KiB
+    ///
+    /// Values outside the supported range are clamped to the nearest bound.
+    pub fn with_cache_size<P: AsRef<Path>>(path: P, cache_size_kib: i64) -> Result<Self> {
         let path = path.as_ref().to_path_buf();
 
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
 
-        let conn = Self::create_connection(&path)?;
+        let conn = Self::create_connection(&path, cache_size_kib)?;
 
         Ok(Self {
             path,
@@ -28,7 +51,7 @@ impl Database {
         })
     }
 
-    fn create_connection(path: &Path) -> Result<Connection> {
+    fn create_connection(path: &Path, cache_size_kib: i64) -> Result<Connection> {
         let conn = Connection::open_with_flags(
             path,
             OpenFlags::SQLITE_OPEN_READ_WRITE
@@ -42,10 +65,12 @@ impl Database {
             PRAGMA journal_mode = WAL;
             PRAGMA synchronous = NORMAL;
             PRAGMA foreign_keys = ON;
-            PRAGMA cache_size = -20000;
             "#,
         )?;
 
+        // Negative values are interpreted by SQLite as KiB instead of pages
+        conn.pragma_update(None, "cache_size", -clamp_cache_size(cache_size_kib))?;
+
         Ok(conn)
     }
 
@@ -69,11 +94,161 @@ impl Database {
         })
     }
 
+    /// Run a passive WAL checkpoint, copying as many frames as possible back
+    /// into the database file without waiting on readers or writers
+    pub fn checkpoint_passive(&self) -> Result<WalCheckpoint> {
+        self.with_connection(|conn| {
+            let checkpoint = conn.query_row("PRAGMA wal_checkpoint(PASSIVE)", [], |row| {
+                Ok(WalCheckpoint {
+                    log_frames: row.get(1)?,
+                    checkpointed_frames: row.get(2)?,
+                })
+            })?;
+
+            Ok(checkpoint)
+        })
+    }
+
+    /// Copy every WAL frame back into the database file and truncate the WAL
+    pub fn checkpoint(&self) -> Result<()> {
+        self.with_connection(|conn| {
+            conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+            Ok(())
+        })
+    }
+
+    /// Rebuild the database file, releasing pages freed by deleted rows
+    ///
+    /// VACUUM cannot run inside a transaction, so this uses the connection
+    /// directly rather than going through [`Database::transaction`].
+    pub fn vacuum(&self) -> Result<()> {
+        self.with_connection(|conn| {
+            conn.execute_batch("VACUUM")?;
+            Ok(())
+        })
+    }
+
+    /// Refresh query planner statistics, rebuild the file with VACUUM and
+    /// truncate the WAL
+    ///
+    /// Returns the number of bytes reclaimed from the database file.
+    pub fn optimize(&self) -> Result<u64> {
+        self.with_connection(|conn| {
+            let size_before = database_size(conn)?;
+
+            conn.execute_batch("ANALYZE; VACUUM;")?;
+            conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+
+            let size_after = database_size(conn)?;
+
+            Ok(size_before.saturating_sub(size_after))
+        })
+    }
+
+    /// Schema version recorded in the database, or 0 if it was never migrated
+    pub fn schema_version(&self) -> Result<i32> {
+        self.with_connection(|conn| migrations::current_schema_version(conn))
+    }
+
+    /// Whether migrations still have to run to reach the supported schema
+    pub fn needs_migration(&self) -> Result<bool> {
+        Ok(self.schema_version()? < migrations::SCHEMA_VERSION)
+    }
+
+    /// Copy the database next to itself as `<file>.bak-<version>` when
+    /// migrations are pending
+    ///
+    /// Nothing is written for a brand-new, empty database. Returns the path
+    /// of the backup, if one was made.
+    pub fn backup_before_migration(&self) -> Result<Option<PathBuf>> {
+        let version = self.schema_version()?;
+
+        if version >= migrations::SCHEMA_VERSION {
+            return Ok(None);
+        }
+
+        let has_tables = self.with_connection(|conn| {
+            let exists = conn.query_row(
+                "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table')",
+                [],
+                |row| row.get::<_, bool>(0),
+            )?;
+
+            Ok(exists)
+        })?;
+
+        if version == 0 && !has_tables {
+            return Ok(None);
+        }
+
+        let mut file_name = self.path.file_name().unwrap_or_default().to_os_string();
+        file_name.push(format!(".bak-{}", version));
+
+        let backup_path = self.path.with_file_name(file_name);
+        self.backup_to(&backup_path)?;
+
+        Ok(Some(backup_path))
+    }
+
+    /// Copy the whole database into `dst` using SQLite's online backup API
+    pub fn backup_to<P: AsRef<Path>>(&self, dst: P) -> Result<()> {
+        self.with_connection(|conn| {
+            conn.backup(MAIN_DB, dst, None)?;
+            Ok(())
+        })
+    }
+
+    /// Overwrite the open database with the contents of the backup at `src`
+    ///
+    /// The connection stays open, so every DAO sharing this handle sees the
+    /// restored data immediately.
+    pub fn restore_from<P: AsRef<Path>>(&self, src: P) -> Result<()> {
+        let src = src.as_ref();
+
+        if !src.is_file() {
+            return Err(Error::NotFound(format!(
+                "Backup file not found: {}",
+                src.display()
+            )));
+        }
+
+        self.with_connection(|conn| {
+            conn.restore(MAIN_DB, src, None::<fn(Progress)>)?;
+            Ok(())
+        })
+    }
+
     pub fn path(&self) -> &Path {
         &self.path
     }
 }
 
+/// Size of the main database in bytes, from its page count and page size
+fn database_size(conn: &Connection) -> Result<u64> {
+    let size: i64 = conn.query_row(
+        "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+        [],
+        |row| row.get(0),
+    )?;
+
+    Ok(size.max(0) as u64)
+}
+
+fn clamp_cache_size(cache_size_kib: i64) -> i64 {
+    let clamped = cache_size_kib.clamp(MIN_CACHE_SIZE_KIB, MAX_CACHE_SIZE_KIB);
+
+    if clamped != cache_size_kib {
+        log::warn!(
+            target: "playtime::db",
+            "Cache size {} KiB out of range, clamping to {} KiB",
+            cache_size_kib,
+            clamped
+        );
+    }
+
+    clamped
+}
+
 impl std::fmt::Debug for Database {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Database")
@@ -97,4 +272,142 @@ mod tests {
         // Cleanup
         std::fs::remove_file(db_path).ok();
     }
+
+    #[test]
+    fn test_cache_size_is_clamped() {
+        assert_eq!(clamp_cache_size(0), MIN_CACHE_SIZE_KIB);
+        assert_eq!(clamp_cache_size(-5), MIN_CACHE_SIZE_KIB);
+        assert_eq!(clamp_cache_size(i64::MAX), MAX_CACHE_SIZE_KIB);
+        assert_eq!(
+            clamp_cache_size(DEFAULT_CACHE_SIZE_KIB),
+            DEFAULT_CACHE_SIZE_KIB
+        );
+
+        let db_path = std::env::temp_dir().join(format!("test_cache_{}.db", uuid::Uuid::new_v4()));
+        let db = Database::with_cache_size(&db_path, 10_000_000).unwrap();
+
+        let cache_size: i64 = db
+            .with_connection(|conn| Ok(conn.query_row("PRAGMA cache_size", [], |row| row.get(0))?))
+            .unwrap();
+        assert_eq!(cache_size, -MAX_CACHE_SIZE_KIB);
+
+        std::fs::remove_file(db_path).ok();
+    }
+
+    #[test]
+    fn test_backup_and_restore() {
+        use crate::db::GamesDao;
+        use crate::db::migrations::run_migrations;
+        use crate::models::Game;
+
+        let db_path =
+            std::env::temp_dir().join(format!("test_restore_{}.db", uuid::Uuid::new_v4()));
+        let backup_path =
+            std::env::temp_dir().join(format!("test_restore_backup_{}.db", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::new(&db_path).unwrap());
+        db.with_connection(run_migrations).unwrap();
+
+        let games = GamesDao::new(Arc::clone(&db));
+        games.save_game(&Game::new("123", "Test Game")).unwrap();
+        db.backup_to(&backup_path).unwrap();
+
+        db.with_connection(|conn| {
+            conn.execute("DELETE FROM game_dict", [])?;
+            Ok(())
+        })
+        .unwrap();
+        assert!(games.get_game("123").unwrap().is_none());
+
+        db.restore_from(&backup_path).unwrap();
+        assert_eq!(games.get_game("123").unwrap().unwrap().name, "Test Game");
+
+        assert!(
+            db.restore_from(backup_path.with_extension("missing"))
+                .unwrap_err()
+                .is_not_found()
+        );
+
+        std::fs::remove_file(db_path).ok();
+        std::fs::remove_file(backup_path).ok();
+    }
+
+    #[test]
+    fn test_schema_version() {
+        let db_path =
+            std::env::temp_dir().join(format!("test_schema_version_{}.db", uuid::Uuid::new_v4()));
+        let db = Database::new(&db_path).unwrap();
+
+        assert_eq!(db.schema_version().unwrap(), 0);
+        assert!(db.needs_migration().unwrap());
+
+        db.with_connection(migrations::run_migrations).unwrap();
+
+        assert_eq!(db.schema_version().unwrap(), migrations::SCHEMA_VERSION);
+        assert!(!db.needs_migration().unwrap());
+
+        std::fs::remove_file(db_path).ok();
+    }
+
+    #[test]
+    fn test_optimize_reclaims_space() {
+        let db_path =
+            std::env::temp_dir().join(format!("test_optimize_{}.db", uuid::Uuid::new_v4()));
+        let db = Database::new(&db_path).unwrap();
+
+        db.with_connection(|conn| {
+            conn.execute_batch(
+                r#"
+                CREATE TABLE filler (id INTEGER PRIMARY KEY, payload TEXT);
+                WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 2000)
+                INSERT INTO filler (payload) SELECT printf('%.500c', 'x') FROM n;
+                DELETE FROM filler WHERE id > 10;
+                "#,
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        assert!(db.optimize().unwrap() > 0);
+
+        let remaining: i64 = db
+            .with_connection(|conn| {
+                Ok(conn.query_row("SELECT COUNT(*) FROM filler", [], |row| row.get(0))?)
+            })
+            .unwrap();
+        assert_eq!(remaining, 10);
+
+        std::fs::remove_file(db_path).ok();
+    }
+
+    #[test]
+    fn test_vacuum_and_checkpoint_shrink_file() {
+        let db_path = std::env::temp_dir().join(format!("test_vacuum_{}.db", uuid::Uuid::new_v4()));
+        let db = Database::new(&db_path).unwrap();
+
+        db.with_connection(|conn| {
+            conn.execute_batch(
+                r#"
+                CREATE TABLE filler (id INTEGER PRIMARY KEY, payload TEXT);
+                WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 2000)
+                INSERT INTO filler (payload) SELECT printf('%.500c', 'x') FROM n;
+                "#,
+            )?;
+            Ok(())
+        })
+        .unwrap();
+        db.checkpoint().unwrap();
+        let size_before = std::fs::metadata(&db_path).unwrap().len();
+
+        db.with_connection(|conn| {
+            conn.execute("DELETE FROM filler WHERE id > 10", [])?;
+            Ok(())
+        })
+        .unwrap();
+        db.vacuum().unwrap();
+        db.checkpoint().unwrap();
+
+        assert!(std::fs::metadata(&db_path).unwrap().len() < size_before);
+
+        std::fs::remove_file(db_path).ok();
+    }
 }
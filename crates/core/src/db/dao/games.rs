@@ -1,10 +1,13 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use rusqlite::{OptionalExtension, params};
+use rusqlite::types::Type;
+use rusqlite::{OptionalExtension, Row, params};
 
 use crate::db::Database;
-use crate::error::Result;
-use crate::models::{ChecksumAlgorithm, Game, GameChecksum, GameStatistics};
+use crate::db::dao::{LAST_SESSION_DURATION_SQL, game_statistics_from_row};
+use crate::error::{Error, Result};
+use crate::models::{ChecksumAlgorithm, ChecksumMeta, Game, GameChecksum, GameStatistics};
 
 #[derive(Clone)]
 pub struct GamesDao {
@@ -46,6 +49,207 @@ impl GamesDao {
         })
     }
 
+    /// Rename a game on behalf of the user
+    ///
+    /// The name is trimmed and flagged as user-set so automatic tracking
+    /// won't replace it.
+    pub fn rename_game(&self, game_id: &str, name: &str) -> Result<()> {
+        let name = name.trim();
+
+        if name.is_empty() {
+            return Err(Error::InvalidInput("Game name must not be empty".into()));
+        }
+
+        self.db.with_connection(|conn| {
+            let updated = conn.execute(
+                "UPDATE game_dict SET name = ?2, is_user_set = 1 WHERE game_id = ?1",
+                params![game_id, name],
+            )?;
+
+            if updated == 0 {
+                return Err(Error::NotFound(format!("Game {} not found", game_id)));
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Delete a game together with its sessions, cached total, checksums,
+    /// goals and metadata
+    pub fn delete_game(&self, game_id: &str) -> Result<()> {
+        self.db.transaction(|tx| {
+            let exists = tx
+                .query_row(
+                    "SELECT 1 FROM game_dict WHERE game_id = ?1",
+                    params![game_id],
+                    |_| Ok(()),
+                )
+                .optional()?
+                .is_some();
+
+            if !exists {
+                return Err(Error::NotFound(format!("Game {} not found", game_id)));
+            }
+
+            for table in [
+                "play_time",
+                "overall_time",
+                "game_file_checksum",
+                "game_total_goal",
+                "goals",
+                "game_metadata",
+                "game_dict",
+            ] {
+                tx.execute(
+                    &format!("DELETE FROM {} WHERE game_id = ?1", table),
+                    params![game_id],
+                )?;
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Move everything recorded for `source_id` onto `target_id` and remove
+    /// the source game
+    ///
+    /// Sessions and the cached total are combined. Checksums, metadata and
+    /// goals move over unless the target already has the same entry.
+    pub fn merge_games(&self, source_id: &str, target_id: &str) -> Result<()> {
+        if source_id == target_id {
+            return Err(Error::InvalidInput(
+                "Cannot merge a game into itself".into(),
+            ));
+        }
+
+        self.db.transaction(|tx| {
+            for game_id in [source_id, target_id] {
+                let exists = tx
+                    .query_row(
+                        "SELECT 1 FROM game_dict WHERE game_id = ?1",
+                        params![game_id],
+                        |_| Ok(()),
+                    )
+                    .optional()?
+                    .is_some();
+
+                if !exists {
+                    return Err(Error::NotFound(format!("Game {} not found", game_id)));
+                }
+            }
+
+            tx.execute(
+                "UPDATE play_time SET game_id = ?2 WHERE game_id = ?1",
+                params![source_id, target_id],
+            )?;
+
+            tx.execute(
+                r#"
+                INSERT INTO overall_time (game_id, duration)
+                SELECT ?2, duration FROM overall_time WHERE game_id = ?1
+                ON CONFLICT(game_id) DO UPDATE SET duration = duration + excluded.duration
+                "#,
+                params![source_id, target_id],
+            )?;
+
+            for table in [
+                "game_file_checksum",
+                "game_metadata",
+                "game_total_goal",
+                "goals",
+            ] {
+                tx.execute(
+                    &format!(
+                        "UPDATE OR IGNORE {} SET game_id = ?2 WHERE game_id = ?1",
+                        table
+                    ),
+                    params![source_id, target_id],
+                )?;
+            }
+
+            for table in [
+                "overall_time",
+                "game_file_checksum",
+                "game_metadata",
+                "game_total_goal",
+                "goals",
+                "game_dict",
+            ] {
+                tx.execute(
+                    &format!("DELETE FROM {} WHERE game_id = ?1", table),
+                    params![source_id],
+                )?;
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Find groups of different game ids that share a name
+    ///
+    /// With `case_insensitive`, names differing only in ASCII case are grouped
+    /// together. Groups are ordered by name and games within a group by id.
+    pub fn find_duplicate_names(&self, case_insensitive: bool) -> Result<Vec<Vec<Game>>> {
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                WITH keyed AS (
+                    SELECT
+                        game_id,
+                        name,
+                        CASE WHEN ?1 THEN LOWER(name) ELSE name END as name_key
+                    FROM game_dict
+                )
+                SELECT game_id, name, name_key
+                FROM keyed
+                WHERE name_key IN (
+                    SELECT name_key FROM keyed GROUP BY name_key HAVING COUNT(*) > 1
+                )
+                ORDER BY name_key, game_id
+                "#,
+            )?;
+
+            let rows = stmt
+                .query_map(params![case_insensitive], |row| {
+                    Ok((
+                        Game::new(row.get::<_, String>(0)?, row.get::<_, String>(1)?),
+                        row.get::<_, String>(2)?,
+                    ))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            let mut groups: Vec<Vec<Game>> = Vec::new();
+            let mut last_key: Option<String> = None;
+
+            for (game, key) in rows {
+                if last_key.as_ref() == Some(&key) {
+                    if let Some(group) = groups.last_mut() {
+                        group.push(game);
+                    }
+                } else {
+                    groups.push(vec![game]);
+                    last_key = Some(key);
+                }
+            }
+
+            Ok(groups)
+        })
+    }
+
+    /// Insert a game only if its id is not stored yet, keeping any existing
+    /// name. Returns whether a new row was inserted.
+    pub fn save_game_if_absent(&self, game: &Game) -> Result<bool> {
+        self.db.with_connection(|conn| {
+            let inserted = conn.execute(
+                "INSERT INTO game_dict (game_id, name)
+                 VALUES (?1, ?2)
+                 ON CONFLICT(game_id) DO NOTHING",
+                params![&game.id, &game.name],
+            )?;
+            Ok(inserted > 0)
+        })
+    }
+
     pub fn get_all_games(&self) -> Result<Vec<Game>> {
         self.db.with_connection(|conn| {
             let mut stmt = conn.prepare("SELECT game_id, name FROM game_dict ORDER BY name")?;
@@ -65,46 +269,66 @@ impl GamesDao {
 
     pub fn get_game_with_stats(&self, game_id: &str) -> Result<Option<GameStatistics>> {
         self.db.with_connection(|conn| {
-            let mut stmt = conn.prepare(
+            let mut stmt = conn.prepare(&format!(
                 r#"
                 SELECT
                     g.game_id,
                     g.name,
-                    COALESCE(SUM(pt.time), 0) as total_time,
+                    CAST(COALESCE(SUM(pt.duration), 0) AS INTEGER) as total_time,
                     COUNT(pt.id) as total_sessions,
-                    MAX(pt.date) as last_played
+                    MAX(pt.date_time) as last_played,
+                    {} as last_session_duration
                 FROM game_dict g
                 LEFT JOIN play_time pt ON g.game_id = pt.game_id
                 WHERE g.game_id = ?1
                 GROUP BY g.game_id, g.name
                 "#,
-            )?;
+                LAST_SESSION_DURATION_SQL
+            ))?;
 
             let stats = stmt
-                .query_row(params![game_id], |row| {
-                    Ok(GameStatistics {
-                        game: Game {
-                            id: row.get(0)?,
-                            name: row.get(1)?,
-                        },
-                        total_time: row.get(2)?,
-                        total_sessions: row.get(3)?,
-                        last_played: row
-                            .get::<_, Option<String>>(4)?
-                            .and_then(|s| s.parse().ok()),
-                        last_session_duration: None,
-                    })
-                })
+                .query_row(params![game_id], game_statistics_from_row)
                 .optional()?;
 
             Ok(stats)
         })
     }
 
-    pub fn save_game_checksum(&self, checksum: &GameChecksum) -> Result<()> {
+    /// Get every game in the dictionary with its totals in one query,
+    /// ordered by name
+    ///
+    /// Games without sessions are included with a total of zero.
+    pub fn get_all_games_with_stats(&self) -> Result<Vec<GameStatistics>> {
         self.db.with_connection(|conn| {
-            self.save_game(&checksum.game)?;
+            let mut stmt = conn.prepare(&format!(
+                r#"
+                SELECT
+                    g.game_id,
+                    g.name,
+                    CAST(COALESCE(SUM(pt.duration), 0) AS INTEGER) as total_time,
+                    COUNT(pt.id) as total_sessions,
+                    MAX(pt.date_time) as last_played,
+                    {} as last_session_duration
+                FROM game_dict g
+                LEFT JOIN play_time pt ON g.game_id = pt.game_id
+                GROUP BY g.game_id, g.name
+                ORDER BY g.name
+                "#,
+                LAST_SESSION_DURATION_SQL
+            ))?;
 
+            let stats = stmt
+                .query_map([], game_statistics_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(stats)
+        })
+    }
+
+    pub fn save_game_checksum(&self, checksum: &GameChecksum) -> Result<()> {
+        self.save_game(&checksum.game)?;
+
+        self.db.with_connection(|conn| {
             conn.execute(
                 r#"
                 INSERT INTO game_file_checksum
@@ -148,11 +372,7 @@ impl GamesDao {
                             name: row.get(1)?,
                         },
                         checksum: row.get(2)?,
-                        algorithm: match row.get::<_, String>(3)?.as_str() {
-                            "sha256" => ChecksumAlgorithm::Sha256,
-                            "md5" => ChecksumAlgorithm::Md5,
-                            _ => ChecksumAlgorithm::Sha256,
-                        },
+                        algorithm: algorithm_from_row(row, 3)?,
                         chunk_size: row.get::<_, i64>(4)? as usize,
                         created_at: row
                             .get::<_, Option<String>>(5)?
@@ -167,37 +387,191 @@ impl GamesDao {
             Ok(checksums)
         })
     }
+
+    /// Delete one stored checksum, returning whether it existed
+    pub fn delete_checksum(
+        &self,
+        game_id: &str,
+        checksum: &str,
+        algorithm: ChecksumAlgorithm,
+    ) -> Result<bool> {
+        self.db.with_connection(|conn| {
+            let deleted = conn.execute(
+                "DELETE FROM game_file_checksum
+                 WHERE game_id = ?1 AND checksum = ?2 AND algorithm = ?3",
+                params![game_id, checksum, algorithm.to_string()],
+            )?;
+            Ok(deleted > 0)
+        })
+    }
+
+    /// Delete every stored checksum of a game, returning how many were
+    /// removed
+    pub fn delete_all_checksums(&self, game_id: &str) -> Result<usize> {
+        self.db.with_connection(|conn| {
+            let deleted = conn.execute(
+                "DELETE FROM game_file_checksum WHERE game_id = ?1",
+                params![game_id],
+            )?;
+            Ok(deleted)
+        })
+    }
+
+    /// Find every game with a stored checksum matching `checksum` under
+    /// `algorithm`, ordered by name
+    pub fn find_games_by_checksum(
+        &self,
+        checksum: &str,
+        algorithm: ChecksumAlgorithm,
+    ) -> Result<Vec<Game>> {
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT DISTINCT g.game_id, g.name
+                FROM game_file_checksum gfc
+                JOIN game_dict g ON gfc.game_id = g.game_id
+                WHERE gfc.checksum = ?1 AND gfc.algorithm = ?2
+                ORDER BY g.name
+                "#,
+            )?;
+
+            let games = stmt
+                .query_map(params![checksum, algorithm.to_string()], |row| {
+                    Ok(Game {
+                        id: row.get(0)?,
+                        name: row.get(1)?,
+                    })
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(games)
+        })
+    }
+
+    /// Get the algorithm, chunk size and update time of a game's stored
+    /// checksums without loading the digests
+    pub fn get_checksum_meta(&self, game_id: &str) -> Result<Vec<ChecksumMeta>> {
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT algorithm, chunk_size, updated_at
+                FROM game_file_checksum
+                WHERE game_id = ?1
+                ORDER BY algorithm, updated_at
+                "#,
+            )?;
+
+            let meta = stmt
+                .query_map(params![game_id], |row| {
+                    Ok(ChecksumMeta {
+                        algorithm: algorithm_from_row(row, 0)?,
+                        chunk_size: row.get::<_, i64>(1)? as usize,
+                        updated_at: row
+                            .get::<_, Option<String>>(2)?
+                            .and_then(|s| s.parse().ok()),
+                    })
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(meta)
+        })
+    }
+
+    /// Set the total playtime a game should reach, in seconds
+    pub fn set_total_time_goal(&self, game_id: &str, target_seconds: i64) -> Result<()> {
+        if target_seconds <= 0 {
+            return Err(Error::InvalidInput("Goal must be positive".into()));
+        }
+
+        self.db.with_connection(|conn| {
+            conn.execute(
+                "INSERT INTO game_total_goal (game_id, target_seconds)
+                 VALUES (?1, ?2)
+                 ON CONFLICT(game_id) DO UPDATE SET target_seconds = ?2",
+                params![game_id, target_seconds],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn get_total_time_goal(&self, game_id: &str) -> Result<Option<i64>> {
+        self.db.with_connection(|conn| {
+            let goal = conn
+                .query_row(
+                    "SELECT target_seconds FROM game_total_goal WHERE game_id = ?1",
+                    params![game_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            Ok(goal)
+        })
+    }
+
+    /// Store a metadata value for a game, replacing any previous value
+    pub fn set_metadata(&self, game_id: &str, key: &str, value: &str) -> Result<()> {
+        self.db.with_connection(|conn| {
+            conn.execute(
+                "INSERT INTO game_metadata (game_id, key, value)
+                 VALUES (?1, ?2, ?3)
+                 ON CONFLICT(game_id, key) DO UPDATE SET value = ?3",
+                params![game_id, key, value],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn get_metadata(&self, game_id: &str, key: &str) -> Result<Option<String>> {
+        self.db.with_connection(|conn| {
+            let value = conn
+                .query_row(
+                    "SELECT value FROM game_metadata WHERE game_id = ?1 AND key = ?2",
+                    params![game_id, key],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            Ok(value)
+        })
+    }
+
+    pub fn get_all_metadata(&self, game_id: &str) -> Result<HashMap<String, String>> {
+        self.db.with_connection(|conn| {
+            let mut stmt =
+                conn.prepare("SELECT key, value FROM game_metadata WHERE game_id = ?1")?;
+
+            let metadata = stmt
+                .query_map(params![game_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<std::result::Result<HashMap<_, _>, _>>()?;
+
+            Ok(metadata)
+        })
+    }
+}
+
+/// Parse the checksum algorithm stored in column `idx`
+fn algorithm_from_row(row: &Row, idx: usize) -> rusqlite::Result<ChecksumAlgorithm> {
+    row.get::<_, String>(idx)?
+        .parse()
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(idx, Type::Text, Box::new(e)))
 }
 
 #[cfg(test)]
 mod tests {
     use std::env;
-    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use chrono::{DateTime, NaiveDate, Utc};
 
     use super::*;
+    use crate::db::TimeTrackingDao;
+    use crate::db::migrations::run_migrations;
 
     fn setup_test_db() -> Arc<Database> {
         let temp_dir = env::temp_dir();
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
-        let db_path = temp_dir.join(format!("test_games_{}.db", timestamp));
+        let db_path = temp_dir.join(format!("test_games_{}.db", uuid::Uuid::new_v4()));
         let db = Arc::new(Database::new(&db_path).unwrap());
 
-        // Create tables (simplified)
-        db.with_connection(|conn| {
-            conn.execute_batch(
-                r#"
-                CREATE TABLE IF NOT EXISTS game_dict (
-                    game_id TEXT PRIMARY KEY,
-                    name TEXT NOT NULL
-                );
-                "#,
-            )?;
-            Ok(())
-        })
-        .unwrap();
+        db.with_connection(run_migrations).unwrap();
 
         db
     }
@@ -214,4 +588,334 @@ mod tests {
         assert!(retrieved.is_some());
         assert_eq!(retrieved.unwrap().name, "Test Game");
     }
+
+    #[test]
+    fn test_rename_game() {
+        let db = setup_test_db();
+        let dao = GamesDao::new(db);
+
+        dao.save_game(&Game::new("123", "Old Name")).unwrap();
+        dao.save_game(&Game::new("456", "Other")).unwrap();
+
+        dao.rename_game("123", "  New Name ").unwrap();
+        assert_eq!(
+            dao.get_game("123").unwrap(),
+            Some(Game::new("123", "New Name"))
+        );
+        assert_eq!(
+            dao.get_game("456").unwrap(),
+            Some(Game::new("456", "Other"))
+        );
+
+        assert!(
+            dao.rename_game("789", "Missing")
+                .unwrap_err()
+                .is_not_found()
+        );
+        assert!(dao.get_game("789").unwrap().is_none());
+
+        assert!(matches!(
+            dao.rename_game("123", "   "),
+            Err(Error::InvalidInput(_))
+        ));
+        assert_eq!(dao.get_game("123").unwrap().unwrap().name, "New Name");
+    }
+
+    #[test]
+    fn test_save_game_if_absent_keeps_existing_name() {
+        let db = setup_test_db();
+        let dao = GamesDao::new(db);
+
+        assert!(
+            dao.save_game_if_absent(&Game::new("123", "Edited Name"))
+                .unwrap()
+        );
+        assert!(
+            !dao.save_game_if_absent(&Game::new("123", "Scanned Name"))
+                .unwrap()
+        );
+
+        assert_eq!(dao.get_game("123").unwrap().unwrap().name, "Edited Name");
+    }
+
+    #[test]
+    fn test_game_metadata() {
+        let db = setup_test_db();
+        let dao = GamesDao::new(db);
+
+        dao.save_game(&Game::new("123", "Test Game")).unwrap();
+        dao.set_metadata("123", "cover", "cover_v1.png").unwrap();
+        dao.set_metadata("123", "notes", "100% run").unwrap();
+        dao.set_metadata("123", "cover", "cover_v2.png").unwrap();
+
+        assert_eq!(
+            dao.get_metadata("123", "cover").unwrap().as_deref(),
+            Some("cover_v2.png")
+        );
+        assert_eq!(dao.get_metadata("123", "tags").unwrap(), None);
+
+        let all = dao.get_all_metadata("123").unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all["notes"], "100% run");
+
+        assert!(dao.set_metadata("456", "cover", "orphan.png").is_err());
+    }
+
+    #[test]
+    fn test_find_duplicate_names() {
+        let db = setup_test_db();
+        let dao = GamesDao::new(db);
+
+        dao.save_game(&Game::new("1", "ELDEN RING")).unwrap();
+        dao.save_game(&Game::new("2", "Elden Ring")).unwrap();
+        dao.save_game(&Game::new("3", "Hades")).unwrap();
+        dao.save_game(&Game::new("4", "Hades")).unwrap();
+        dao.save_game(&Game::new("5", "Celeste")).unwrap();
+
+        let ids = |groups: Vec<Vec<Game>>| -> Vec<Vec<String>> {
+            groups
+                .into_iter()
+                .map(|group| group.into_iter().map(|game| game.id).collect())
+                .collect()
+        };
+
+        assert_eq!(
+            ids(dao.find_duplicate_names(false).unwrap()),
+            vec![vec!["3", "4"]]
+        );
+        assert_eq!(
+            ids(dao.find_duplicate_names(true).unwrap()),
+            vec![vec!["1", "2"], vec!["3", "4"]]
+        );
+    }
+
+    #[test]
+    fn test_get_checksum_meta() {
+        let db = setup_test_db();
+        let dao = GamesDao::new(db);
+
+        let updated_at = "2024-03-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        for (checksum, algorithm, chunk_size) in [
+            ("abc123", ChecksumAlgorithm::Sha256, 4096),
+            ("def456", ChecksumAlgorithm::Blake2b, 1024),
+        ] {
+            dao.save_game_checksum(&GameChecksum {
+                game: Game::new("123", "Test Game"),
+                checksum: checksum.into(),
+                algorithm,
+                chunk_size,
+                created_at: Some(updated_at),
+                updated_at: Some(updated_at),
+            })
+            .unwrap();
+        }
+
+        let meta = dao.get_checksum_meta("123").unwrap();
+        assert_eq!(
+            meta,
+            vec![
+                ChecksumMeta {
+                    algorithm: ChecksumAlgorithm::Blake2b,
+                    chunk_size: 1024,
+                    updated_at: Some(updated_at),
+                },
+                ChecksumMeta {
+                    algorithm: ChecksumAlgorithm::Sha256,
+                    chunk_size: 4096,
+                    updated_at: Some(updated_at),
+                },
+            ]
+        );
+        assert!(dao.get_checksum_meta("456").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_delete_game() {
+        let db = setup_test_db();
+        let dao = GamesDao::new(Arc::clone(&db));
+        let time_tracking = TimeTrackingDao::new(Arc::clone(&db));
+
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1)
+            .and_then(|d| d.and_hms_opt(12, 0, 0))
+            .unwrap();
+        time_tracking
+            .add_duration("123", "Test Game", 600, date, "manual")
+            .unwrap();
+        time_tracking
+            .add_duration("456", "Other Game", 300, date, "manual")
+            .unwrap();
+        dao.set_metadata("123", "platform", "steam").unwrap();
+
+        dao.delete_game("123").unwrap();
+
+        assert_eq!(time_tracking.get_total_playtime("123").unwrap(), 0);
+        assert!(dao.get_game("123").unwrap().is_none());
+        assert_eq!(time_tracking.get_total_playtime("456").unwrap(), 300);
+
+        assert!(dao.delete_game("123").unwrap_err().is_not_found());
+    }
+
+    #[test]
+    fn test_get_all_games_with_stats() {
+        let db = setup_test_db();
+        let dao = GamesDao::new(Arc::clone(&db));
+
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1)
+            .and_then(|d| d.and_hms_opt(12, 0, 0))
+            .unwrap();
+        TimeTrackingDao::new(Arc::clone(&db))
+            .add_duration("123", "Played", 600, date, "manual")
+            .unwrap();
+        dao.save_game(&Game::new("456", "Never Played")).unwrap();
+
+        let stats = dao.get_all_games_with_stats().unwrap();
+        let totals: Vec<(&str, i64, i64)> = stats
+            .iter()
+            .map(|s| (s.game.id.as_str(), s.total_time, s.total_sessions))
+            .collect();
+        assert_eq!(totals, vec![("456", 0, 0), ("123", 600, 1)]);
+        assert_eq!(stats[0].last_played, None);
+        assert_eq!(stats[0].last_session_duration, None);
+        assert_eq!(stats[1].last_played, Some(date));
+        assert_eq!(stats[1].last_session_duration, Some(600));
+    }
+
+    #[test]
+    fn test_blake2b_checksum_round_trip() {
+        let db = setup_test_db();
+        let dao = GamesDao::new(db);
+
+        dao.save_game_checksum(&GameChecksum {
+            game: Game::new("123", "Test Game"),
+            checksum: "deadbeef".into(),
+            algorithm: ChecksumAlgorithm::Blake2b,
+            chunk_size: 16384,
+            created_at: None,
+            updated_at: None,
+        })
+        .unwrap();
+
+        let checksums = dao.get_game_checksums("123").unwrap();
+        assert_eq!(checksums.len(), 1);
+        assert_eq!(checksums[0].checksum, "deadbeef");
+        assert_eq!(checksums[0].algorithm, ChecksumAlgorithm::Blake2b);
+        assert_eq!(checksums[0].chunk_size, 16384);
+    }
+
+    #[test]
+    fn test_find_games_by_checksum() {
+        let db = setup_test_db();
+        let dao = GamesDao::new(db);
+        let save = |game: Game, checksum: &str, algorithm: ChecksumAlgorithm| {
+            dao.save_game_checksum(&GameChecksum {
+                game,
+                checksum: checksum.into(),
+                algorithm,
+                chunk_size: 16384,
+                created_at: None,
+                updated_at: None,
+            })
+            .unwrap();
+        };
+
+        save(Game::new("123", "Beta"), "abc", ChecksumAlgorithm::Sha256);
+        save(Game::new("456", "Alpha"), "abc", ChecksumAlgorithm::Sha256);
+        save(Game::new("789", "Gamma"), "abc", ChecksumAlgorithm::Blake2b);
+
+        let ids: Vec<String> = dao
+            .find_games_by_checksum("abc", ChecksumAlgorithm::Sha256)
+            .unwrap()
+            .into_iter()
+            .map(|game| game.id)
+            .collect();
+        assert_eq!(ids, vec!["456", "123"]);
+        assert!(
+            dao.find_games_by_checksum("def", ChecksumAlgorithm::Sha256)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_delete_checksum() {
+        let db = setup_test_db();
+        let dao = GamesDao::new(db);
+        for checksum in ["abc", "def"] {
+            dao.save_game_checksum(&GameChecksum {
+                game: Game::new("123", "Test Game"),
+                checksum: checksum.into(),
+                algorithm: ChecksumAlgorithm::Sha256,
+                chunk_size: 16384,
+                created_at: None,
+                updated_at: None,
+            })
+            .unwrap();
+        }
+
+        assert!(
+            !dao.delete_checksum("123", "abc", ChecksumAlgorithm::Blake2b)
+                .unwrap()
+        );
+        assert!(
+            dao.delete_checksum("123", "abc", ChecksumAlgorithm::Sha256)
+                .unwrap()
+        );
+
+        let remaining: Vec<String> = dao
+            .get_game_checksums("123")
+            .unwrap()
+            .into_iter()
+            .map(|checksum| checksum.checksum)
+            .collect();
+        assert_eq!(remaining, vec!["def"]);
+
+        assert_eq!(dao.delete_all_checksums("123").unwrap(), 1);
+        assert_eq!(dao.delete_all_checksums("123").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_merge_games() {
+        let db = setup_test_db();
+        let dao = GamesDao::new(Arc::clone(&db));
+        let time_tracking = TimeTrackingDao::new(Arc::clone(&db));
+
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1)
+            .and_then(|d| d.and_hms_opt(12, 0, 0))
+            .unwrap();
+        time_tracking
+            .add_duration("123", "Shortcut", 600, date, "manual")
+            .unwrap();
+        time_tracking
+            .add_duration("456", "Steam", 300, date, "manual")
+            .unwrap();
+        time_tracking
+            .add_duration("456", "Steam", 100, date, "manual")
+            .unwrap();
+        dao.set_metadata("123", "platform", "steam").unwrap();
+
+        dao.merge_games("123", "456").unwrap();
+
+        assert_eq!(time_tracking.get_total_playtime("456").unwrap(), 1000);
+        assert_eq!(time_tracking.get_game_sessions("456").unwrap().len(), 3);
+        assert!(dao.get_game("123").unwrap().is_none());
+        assert!(time_tracking.get_game_sessions("123").unwrap().is_empty());
+        assert_eq!(
+            dao.get_metadata("456", "platform").unwrap().as_deref(),
+            Some("steam")
+        );
+
+        let overall: Vec<(String, i64)> = db
+            .with_connection(|conn| {
+                let mut stmt = conn.prepare("SELECT game_id, duration FROM overall_time")?;
+                let rows = stmt
+                    .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(rows)
+            })
+            .unwrap();
+        assert_eq!(overall, vec![("456".into(), 1000)]);
+
+        assert!(dao.merge_games("123", "456").unwrap_err().is_not_found());
+        assert!(dao.merge_games("456", "789").unwrap_err().is_not_found());
+    }
 }
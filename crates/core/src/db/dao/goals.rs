@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use rusqlite::{OptionalExtension, params};
+
+use crate::db::Database;
+use crate::error::Result;
+
+/// Period stored for weekly goals
+const WEEKLY: &str = "weekly";
+
+/// Access to the `goals` table, where a `None` game id means all games
+#[derive(Clone)]
+pub struct GoalsDao {
+    db: Arc<Database>,
+}
+
+impl GoalsDao {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Set the weekly playtime budget in seconds, replacing any previous one
+    pub fn set_weekly_goal(&self, game_id: Option<&str>, target_seconds: i64) -> Result<()> {
+        self.db.transaction(|tx| {
+            tx.execute(
+                "DELETE FROM goals WHERE game_id IS ?1 AND period = ?2",
+                params![game_id, WEEKLY],
+            )?;
+            tx.execute(
+                "INSERT INTO goals (game_id, period, target_seconds) VALUES (?1, ?2, ?3)",
+                params![game_id, WEEKLY, target_seconds],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Get the weekly playtime budget in seconds
+    pub fn get_weekly_goal(&self, game_id: Option<&str>) -> Result<Option<i64>> {
+        self.db.with_connection(|conn| {
+            let target = conn
+                .query_row(
+                    "SELECT target_seconds FROM goals WHERE game_id IS ?1 AND period = ?2",
+                    params![game_id, WEEKLY],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            Ok(target)
+        })
+    }
+
+    /// Sum playtime dated from `start` up to but excluding `end`, for one
+    /// game or all of them
+    pub fn get_played_seconds(
+        &self,
+        game_id: Option<&str>,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<i64> {
+        self.db.with_connection(|conn| {
+            let played = conn.query_row(
+                r#"
+                SELECT CAST(COALESCE(SUM(duration), 0) AS INTEGER)
+                FROM play_time
+                WHERE (?1 IS NULL OR game_id = ?1)
+                  AND date_time >= ?2 AND date_time < ?3
+                "#,
+                params![game_id, start.to_string(), end.to_string()],
+                |row| row.get(0),
+            )?;
+
+            Ok(played)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::db::migrations::run_migrations;
+
+    fn setup_test_db() -> Arc<Database> {
+        let temp_dir = env::temp_dir();
+        let db_path = temp_dir.join(format!("test_goals_{}.db", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::new(&db_path).unwrap());
+
+        db.with_connection(run_migrations).unwrap();
+
+        db
+    }
+
+    #[test]
+    fn test_weekly_goal_scopes() {
+        let db = setup_test_db();
+        let dao = GoalsDao::new(Arc::clone(&db));
+
+        db.with_connection(|conn| {
+            conn.execute(
+                "INSERT INTO game_dict (game_id, name) VALUES ('123', 'Test Game')",
+                [],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(dao.get_weekly_goal(None).unwrap(), None);
+
+        dao.set_weekly_goal(None, 36_000).unwrap();
+        dao.set_weekly_goal(Some("123"), 7200).unwrap();
+        dao.set_weekly_goal(None, 18_000).unwrap();
+
+        assert_eq!(dao.get_weekly_goal(None).unwrap(), Some(18_000));
+        assert_eq!(dao.get_weekly_goal(Some("123")).unwrap(), Some(7200));
+        assert_eq!(dao.get_weekly_goal(Some("456")).unwrap(), None);
+    }
+}
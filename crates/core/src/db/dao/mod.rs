@@ -1,7 +1,65 @@
 pub mod games;
+pub mod goals;
+pub mod settings;
 pub mod statistics;
 pub mod time_tracking;
 
 pub use games::GamesDao;
+pub use goals::GoalsDao;
+pub use settings::SettingsDao;
 pub use statistics::StatisticsDao;
 pub use time_tracking::TimeTrackingDao;
+
+use chrono::{Local, NaiveDateTime};
+use rusqlite::Row;
+
+use crate::models::{Game, GameStatistics, PlaySession};
+
+/// Correlated subquery selecting the duration of game `g`'s newest session
+pub(crate) const LAST_SESSION_DURATION_SQL: &str = r#"(
+    SELECT CAST(last.duration AS INTEGER)
+    FROM play_time last
+    WHERE last.game_id = g.game_id
+    ORDER BY last.date_time DESC, last.id DESC
+    LIMIT 1
+)"#;
+
+/// Map a `(game_id, date, duration, checksum)` row to a [`PlaySession`]
+pub(crate) fn play_session_from_row(row: &Row) -> rusqlite::Result<PlaySession> {
+    let date_str: String = row.get(1)?;
+    let date = NaiveDateTime::parse_from_str(&date_str, "%Y-%m-%dT%H:%M:%S")
+        .unwrap_or_else(|_| Local::now().naive_local());
+
+    let started_at = local_timestamp(date);
+    let duration: f64 = row.get(2)?;
+
+    Ok(PlaySession {
+        game_id: row.get(0)?,
+        started_at,
+        ended_at: started_at + duration,
+        duration,
+        checksum: row.get(3)?,
+    })
+}
+
+/// Map a `(game_id, name, total_time, total_sessions, last_played,
+/// last_session_duration)` row to [`GameStatistics`]
+pub(crate) fn game_statistics_from_row(row: &Row) -> rusqlite::Result<GameStatistics> {
+    Ok(GameStatistics {
+        game: Game {
+            id: row.get(0)?,
+            name: row.get(1)?,
+        },
+        total_time: row.get(2)?,
+        total_sessions: row.get(3)?,
+        last_played: row
+            .get::<_, Option<String>>(4)?
+            .and_then(|s| NaiveDateTime::parse_from_str(&s, "%Y-%m-%dT%H:%M:%S").ok()),
+        last_session_duration: row.get(5)?,
+    })
+}
+
+/// Unix timestamp of a stored local wall-clock time
+pub(crate) fn local_timestamp(date: NaiveDateTime) -> f64 {
+    date.and_local_timezone(Local).unwrap().timestamp() as f64
+}
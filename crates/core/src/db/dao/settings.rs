@@ -0,0 +1,134 @@
+use std::sync::Arc;
+
+use rusqlite::{OptionalExtension, params};
+
+use crate::db::Database;
+use crate::error::{Error, Result};
+
+/// Typed access to the `settings` key-value table
+#[derive(Clone)]
+pub struct SettingsDao {
+    db: Arc<Database>,
+}
+
+impl SettingsDao {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Get a text setting, or `default` when it isn't set
+    pub fn get_string(&self, key: &str, default: &str) -> Result<String> {
+        Ok(self.get(key)?.unwrap_or_else(|| default.to_string()))
+    }
+
+    /// Get an integer setting, or `default` when it isn't set
+    pub fn get_i64(&self, key: &str, default: i64) -> Result<i64> {
+        match self.get(key)? {
+            Some(value) => value.parse().map_err(|_| {
+                Error::Internal(format!("Setting {} is not an integer: {}", key, value))
+            }),
+            None => Ok(default),
+        }
+    }
+
+    /// Get a boolean setting, or `default` when it isn't set
+    pub fn get_bool(&self, key: &str, default: bool) -> Result<bool> {
+        match self.get(key)? {
+            Some(value) => value.parse().map_err(|_| {
+                Error::Internal(format!("Setting {} is not a boolean: {}", key, value))
+            }),
+            None => Ok(default),
+        }
+    }
+
+    pub fn set_string(&self, key: &str, value: &str) -> Result<()> {
+        self.set(key, value)
+    }
+
+    pub fn set_i64(&self, key: &str, value: i64) -> Result<()> {
+        self.set(key, &value.to_string())
+    }
+
+    pub fn set_bool(&self, key: &str, value: bool) -> Result<()> {
+        self.set(key, &value.to_string())
+    }
+
+    /// Remove a setting so reads fall back to their default
+    pub fn remove(&self, key: &str) -> Result<()> {
+        self.db.with_connection(|conn| {
+            conn.execute("DELETE FROM settings WHERE key = ?1", params![key])?;
+            Ok(())
+        })
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        self.db.with_connection(|conn| {
+            let value = conn
+                .query_row(
+                    "SELECT value FROM settings WHERE key = ?1",
+                    params![key],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            Ok(value)
+        })
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<()> {
+        self.db.with_connection(|conn| {
+            conn.execute(
+                "INSERT INTO settings (key, value)
+                 VALUES (?1, ?2)
+                 ON CONFLICT(key) DO UPDATE SET value = ?2",
+                params![key, value],
+            )?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::db::migrations::run_migrations;
+
+    fn setup_test_db() -> Arc<Database> {
+        let temp_dir = env::temp_dir();
+        let db_path = temp_dir.join(format!("test_settings_{}.db", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::new(&db_path).unwrap());
+
+        db.with_connection(run_migrations).unwrap();
+
+        db
+    }
+
+    #[test]
+    fn test_typed_settings() {
+        let db = setup_test_db();
+        let dao = SettingsDao::new(db);
+
+        assert_eq!(dao.get_string("attribution", "start").unwrap(), "start");
+        assert_eq!(dao.get_i64("day_start_hour", 0).unwrap(), 0);
+        assert!(dao.get_bool("retention_enabled", true).unwrap());
+
+        dao.set_string("attribution", "end").unwrap();
+        dao.set_i64("day_start_hour", 4).unwrap();
+        dao.set_bool("retention_enabled", false).unwrap();
+
+        assert_eq!(dao.get_string("attribution", "start").unwrap(), "end");
+        assert_eq!(dao.get_i64("day_start_hour", 0).unwrap(), 4);
+        assert!(!dao.get_bool("retention_enabled", true).unwrap());
+
+        dao.set_i64("day_start_hour", -2).unwrap();
+        assert_eq!(dao.get_i64("day_start_hour", 0).unwrap(), -2);
+
+        assert!(dao.get_i64("attribution", 0).is_err());
+        assert!(dao.get_bool("day_start_hour", false).is_err());
+
+        dao.remove("attribution").unwrap();
+        assert_eq!(dao.get_string("attribution", "start").unwrap(), "start");
+    }
+}
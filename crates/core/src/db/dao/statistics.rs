@@ -1,11 +1,22 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
 use std::sync::Arc;
 
-use chrono::{NaiveDate, NaiveDateTime};
-use rusqlite::{OptionalExtension, params};
+use chrono::{Days, NaiveDate, NaiveDateTime};
+use rusqlite::types::Type;
+use rusqlite::{OptionalExtension, params, params_from_iter};
 
 use crate::db::Database;
-use crate::error::Result;
-use crate::models::{DailyGameStats, DailyStatistics, Game, GameStatistics, SessionInfo};
+use crate::db::dao::{
+    LAST_SESSION_DURATION_SQL, game_statistics_from_row, local_timestamp, play_session_from_row,
+};
+use crate::error::{Error, Result};
+use crate::models::{
+    AdjustmentSummary, DailyGameStats, DailyStatistics, Game, GameStatistics, LibraryTotals,
+    PlaySession, SessionInfo, StatisticsSnapshot, WeeklyGameStats, WeeklyStatistics,
+};
+use crate::utils::start_of_week;
 
 #[derive(Clone)]
 pub struct StatisticsDao {
@@ -19,37 +30,26 @@ impl StatisticsDao {
 
     pub fn get_overall_statistics(&self) -> Result<Vec<GameStatistics>> {
         self.db.with_connection(|conn| {
-            let mut stmt = conn.prepare(
+            let mut stmt = conn.prepare(&format!(
                 r#"
                 SELECT
                     g.game_id,
                     g.name,
-                    COALESCE(SUM(pt.time), 0) as total_time,
+                    CAST(COALESCE(SUM(pt.duration), 0) AS INTEGER) as total_time,
                     COUNT(pt.id) as total_sessions,
-                    MAX(pt.date) as last_played
+                    MAX(pt.date_time) as last_played,
+                    {} as last_session_duration
                 FROM game_dict g
                 LEFT JOIN play_time pt ON g.game_id = pt.game_id
                 GROUP BY g.game_id, g.name
                 HAVING total_time > 0
                 ORDER BY total_time DESC
                 "#,
-            )?;
+                LAST_SESSION_DURATION_SQL
+            ))?;
 
             let stats = stmt
-                .query_map([], |row| {
-                    Ok(GameStatistics {
-                        game: Game {
-                            id: row.get(0)?,
-                            name: row.get(1)?,
-                        },
-                        total_time: row.get(2)?,
-                        total_sessions: row.get(3)?,
-                        last_played: row.get::<_, Option<String>>(4)?.and_then(|s| {
-                            NaiveDateTime::parse_from_str(&s, "%Y-%m-%dT%H:%M:%S").ok()
-                        }),
-                        last_session_duration: None,
-                    })
-                })?
+                .query_map([], game_statistics_from_row)?
                 .collect::<std::result::Result<Vec<_>, _>>()?;
 
             Ok(stats)
@@ -61,28 +61,55 @@ impl StatisticsDao {
         start_date: NaiveDate,
         end_date: NaiveDate,
     ) -> Result<Vec<DailyStatistics>> {
+        self.get_daily_statistics_with_rollover(start_date, end_date, 0)
+    }
+
+    /// Daily statistics where each day starts at `day_start_hour` instead of
+    /// midnight, so late-night sessions count toward the previous day
+    pub fn get_daily_statistics_with_rollover(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        day_start_hour: u32,
+    ) -> Result<Vec<DailyStatistics>> {
+        if day_start_hour > 23 {
+            return Err(Error::InvalidInput(
+                "Day start hour must be between 0 and 23".into(),
+            ));
+        }
+
+        let day_shift = format!("-{} hours", day_start_hour);
+
         self.db.with_connection(|conn| {
             let mut stmt = conn.prepare(
                 r#"
                 SELECT
-                    DATE(pt.date) as play_date,
+                    DATE(session_date, ?3) as play_date,
                     g.game_id,
                     g.name,
-                    SUM(pt.time) as total_time,
-                    pt.date,
-                    pt.time,
+                    CAST(SUM(session_time) AS INTEGER) as total_time,
+                    session_date,
+                    session_time,
                     pt.migrated,
                     pt.checksum
-                FROM play_time pt
+                FROM (
+                    SELECT
+                        game_id,
+                        date_time as session_date,
+                        duration as session_time,
+                        migrated,
+                        checksum
+                    FROM play_time
+                ) pt
                 JOIN game_dict g ON pt.game_id = g.game_id
-                WHERE DATE(pt.date) BETWEEN ?1 AND ?2
-                GROUP BY DATE(pt.date), g.game_id, g.name, pt.date
-                ORDER BY DATE(pt.date) DESC, total_time DESC
+                WHERE DATE(session_date, ?3) BETWEEN ?1 AND ?2
+                GROUP BY DATE(session_date, ?3), g.game_id, g.name, session_date
+                ORDER BY DATE(session_date, ?3) DESC, total_time DESC
                 "#,
             )?;
 
             let rows = stmt.query_map(
-                params![start_date.to_string(), end_date.to_string()],
+                params![start_date.to_string(), end_date.to_string(), day_shift],
                 |row| {
                     Ok((
                         row.get::<_, String>(0)?,         // date
@@ -157,10 +184,27 @@ impl StatisticsDao {
                     })
                     .collect();
 
-                result.push(DailyStatistics { date, games });
+                result.push(DailyStatistics {
+                    date,
+                    games,
+                    note: None,
+                });
             }
 
-            result.sort_by(|a, b| b.date.cmp(&a.date));
+            let mut note_stmt =
+                conn.prepare("SELECT date, note FROM day_note WHERE date BETWEEN ?1 AND ?2")?;
+            let mut notes = note_stmt
+                .query_map(
+                    params![start_date.to_string(), end_date.to_string()],
+                    |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+                )?
+                .collect::<std::result::Result<HashMap<_, _>, _>>()?;
+
+            for stats in &mut result {
+                stats.note = notes.remove(&stats.date.to_string());
+            }
+
+            result.sort_by_key(|stats| std::cmp::Reverse(stats.date));
             Ok(result)
         })
     }
@@ -169,37 +213,1830 @@ impl StatisticsDao {
         self.db.with_connection(|conn| {
             let result = conn
                 .query_row(
-                    r#"
+                    &format!(
+                        r#"
                 SELECT
                     g.game_id,
                     g.name,
-                    COALESCE(SUM(pt.time), 0) as total_time,
+                    CAST(COALESCE(SUM(pt.duration), 0) AS INTEGER) as total_time,
                     COUNT(pt.id) as total_sessions,
-                    MAX(pt.date) as last_played
+                    MAX(pt.date_time) as last_played,
+                    {} as last_session_duration
                 FROM game_dict g
                 LEFT JOIN play_time pt ON g.game_id = pt.game_id
                 WHERE g.game_id = ?1
                 GROUP BY g.game_id, g.name
                 "#,
+                        LAST_SESSION_DURATION_SQL
+                    ),
                     params![game_id],
+                    game_statistics_from_row,
+                )
+                .optional()?;
+
+            Ok(result)
+        })
+    }
+
+    /// Count sessions per duration bucket.
+    ///
+    /// `buckets` are ascending upper bounds in seconds (inclusive). The
+    /// returned vector has one count per bucket plus a trailing overflow
+    /// count for sessions longer than the last bound.
+    pub fn get_session_length_histogram(
+        &self,
+        game_id: Option<&str>,
+        buckets: &[i64],
+    ) -> Result<Vec<i64>> {
+        if buckets.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(Error::InvalidInput(
+                "Histogram buckets must be strictly ascending".into(),
+            ));
+        }
+
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT CAST(duration AS INTEGER)
+                FROM play_time
+                WHERE ?1 IS NULL OR game_id = ?1
+                "#,
+            )?;
+
+            let mut counts = vec![0_i64; buckets.len() + 1];
+
+            let durations = stmt.query_map(params![game_id], |row| row.get::<_, i64>(0))?;
+
+            for duration in durations {
+                let duration = duration?;
+                let index = buckets.partition_point(|&bound| bound < duration);
+                counts[index] += 1;
+            }
+
+            Ok(counts)
+        })
+    }
+
+    /// Store the current library totals as a trend point
+    pub fn take_snapshot(&self) -> Result<()> {
+        let now = chrono::Local::now().naive_local();
+
+        self.db.with_connection(|conn| {
+            conn.execute(
+                r#"
+                INSERT INTO stats_snapshot (taken_at, total_time, game_count)
+                SELECT
+                    ?1,
+                    CAST(COALESCE(SUM(duration), 0) AS INTEGER),
+                    COUNT(DISTINCT game_id)
+                FROM play_time
+                "#,
+                params![now.format("%Y-%m-%dT%H:%M:%S").to_string()],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn get_snapshots(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<StatisticsSnapshot>> {
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT taken_at, CAST(total_time AS INTEGER), game_count
+                FROM stats_snapshot
+                WHERE DATE(taken_at) BETWEEN ?1 AND ?2
+                ORDER BY taken_at, id
+                "#,
+            )?;
+
+            let snapshots = stmt
+                .query_map(
+                    params![start_date.to_string(), end_date.to_string()],
                     |row| {
-                        Ok(GameStatistics {
-                            game: Game {
-                                id: row.get(0)?,
-                                name: row.get(1)?,
-                            },
-                            total_time: row.get(2)?,
-                            total_sessions: row.get(3)?,
-                            last_played: row.get::<_, Option<String>>(4)?.and_then(|s| {
-                                NaiveDateTime::parse_from_str(&s, "%Y-%m-%dT%H:%M:%S").ok()
-                            }),
-                            last_session_duration: None,
+                        let taken_at: String = row.get(0)?;
+                        Ok(StatisticsSnapshot {
+                            taken_at: NaiveDateTime::parse_from_str(&taken_at, "%Y-%m-%dT%H:%M:%S")
+                                .unwrap_or_default(),
+                            total_time: row.get(1)?,
+                            game_count: row.get(2)?,
                         })
                     },
+                )?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(snapshots)
+        })
+    }
+
+    /// Find the date on which a game's running total first reached
+    /// `threshold_secs`
+    pub fn get_milestone_date(
+        &self,
+        game_id: &str,
+        threshold_secs: i64,
+    ) -> Result<Option<NaiveDate>> {
+        if threshold_secs <= 0 {
+            return Err(Error::InvalidInput(
+                "Milestone threshold must be positive".into(),
+            ));
+        }
+
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT
+                    DATE(date_time) as session_day,
+                    CAST(duration AS INTEGER) as session_time
+                FROM play_time
+                WHERE game_id = ?1
+                ORDER BY date_time ASC
+                "#,
+            )?;
+
+            let sessions = stmt.query_map(params![game_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })?;
+
+            let mut running_total = 0_i64;
+
+            for session in sessions {
+                let (day, time) = session?;
+                running_total += time;
+
+                if running_total >= threshold_secs {
+                    return Ok(NaiveDate::parse_from_str(&day, "%Y-%m-%d").ok());
+                }
+            }
+
+            Ok(None)
+        })
+    }
+
+    /// Get the most recent session date per game in a single query.
+    ///
+    /// An empty `game_ids` slice returns every game that has sessions.
+    pub fn get_last_played_map(&self, game_ids: &[&str]) -> Result<HashMap<String, NaiveDateTime>> {
+        let filter = if game_ids.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "WHERE game_id IN ({})",
+                vec!["?"; game_ids.len()].join(", ")
+            )
+        };
+
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                r#"
+                SELECT game_id, MAX(date_time) as last_played
+                FROM play_time
+                {}
+                GROUP BY game_id
+                "#,
+                filter
+            ))?;
+
+            let rows = stmt.query_map(params_from_iter(game_ids), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+            })?;
+
+            let mut last_played = HashMap::new();
+
+            for row in rows {
+                let (game_id, date) = row?;
+
+                if let Some(date) =
+                    date.and_then(|s| NaiveDateTime::parse_from_str(&s, "%Y-%m-%dT%H:%M:%S").ok())
+                {
+                    last_played.insert(game_id, date);
+                }
+            }
+
+            Ok(last_played)
+        })
+    }
+
+    /// Compare recorded time against the raw wall-clock span for a game.
+    ///
+    /// Rows without a stored `raw_duration` count their recorded time as raw.
+    pub fn get_adjustment_summary(&self, game_id: &str) -> Result<AdjustmentSummary> {
+        self.db.with_connection(|conn| {
+            let summary = conn.query_row(
+                r#"
+                SELECT
+                    CAST(COALESCE(SUM(duration), 0) AS INTEGER),
+                    CAST(COALESCE(SUM(COALESCE(raw_duration, duration)), 0) AS INTEGER)
+                FROM play_time
+                WHERE game_id = ?1
+                "#,
+                params![game_id],
+                |row| {
+                    Ok(AdjustmentSummary {
+                        adjusted_time: row.get(0)?,
+                        raw_time: row.get(1)?,
+                    })
+                },
+            )?;
+
+            Ok(summary)
+        })
+    }
+
+    /// Sum playtime across every game whose name matches a LIKE pattern.
+    ///
+    /// Only `%` and `_` act as wildcards; a backslash escapes the next
+    /// character so literal wildcards can still be matched.
+    pub fn get_total_for_name_pattern(&self, pattern: &str) -> Result<i64> {
+        self.db.with_connection(|conn| {
+            let total = conn.query_row(
+                r#"
+                SELECT CAST(COALESCE(SUM(pt.duration), 0) AS INTEGER)
+                FROM play_time pt
+                JOIN game_dict g ON pt.game_id = g.game_id
+                WHERE g.name LIKE ?1 ESCAPE '\'
+                "#,
+                params![escape_like_pattern(pattern)],
+                |row| row.get(0),
+            )?;
+
+            Ok(total)
+        })
+    }
+
+    /// Get every session on a single day across all games, in start order
+    pub fn get_sessions_for_day(&self, date: NaiveDate) -> Result<Vec<(Game, PlaySession)>> {
+        let sessions = self
+            .get_sessions_on_date(date)?
+            .into_iter()
+            .map(|(game, info)| {
+                let started_at = local_timestamp(info.date);
+                let session = PlaySession {
+                    game_id: game.id.clone(),
+                    started_at,
+                    ended_at: started_at + info.duration,
+                    duration: info.duration,
+                    checksum: info.checksum,
+                };
+                (game, session)
+            })
+            .collect();
+
+        Ok(sessions)
+    }
+
+    /// Get a chronological timeline of every session on `date` across all
+    /// games, including how each session was recorded
+    pub fn get_sessions_on_date(&self, date: NaiveDate) -> Result<Vec<(Game, SessionInfo)>> {
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT pt.game_id, pt.date_time, pt.duration, pt.migrated, pt.checksum, g.name
+                FROM play_time pt
+                JOIN game_dict g ON pt.game_id = g.game_id
+                WHERE DATE(pt.date_time) = ?1
+                ORDER BY pt.date_time ASC, pt.id ASC
+                "#,
+            )?;
+
+            let sessions = stmt
+                .query_map(params![date.to_string()], |row| {
+                    let session = SessionInfo {
+                        date: row.get::<_, String>(1)?.parse().map_err(|e| {
+                            rusqlite::Error::FromSqlConversionFailure(1, Type::Text, Box::new(e))
+                        })?,
+                        duration: row.get(2)?,
+                        migrated: row.get(3)?,
+                        checksum: row.get(4)?,
+                    };
+                    Ok((
+                        Game::new(row.get::<_, String>(0)?, row.get::<_, String>(5)?),
+                        session,
+                    ))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(sessions)
+        })
+    }
+
+    /// Get every game's single longest session, longest first
+    pub fn get_longest_session_per_game(&self) -> Result<Vec<(Game, PlaySession)>> {
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT game_id, date_time, duration, checksum, name
+                FROM (
+                    SELECT
+                        pt.game_id,
+                        pt.date_time,
+                        pt.duration,
+                        pt.checksum,
+                        g.name,
+                        ROW_NUMBER() OVER (
+                            PARTITION BY pt.game_id
+                            ORDER BY pt.duration DESC, pt.id ASC
+                        ) as rank
+                    FROM play_time pt
+                    JOIN game_dict g ON pt.game_id = g.game_id
+                )
+                WHERE rank = 1
+                ORDER BY duration DESC, game_id ASC
+                "#,
+            )?;
+
+            let sessions = stmt
+                .query_map([], |row| {
+                    let session = play_session_from_row(row)?;
+                    let game = Game::new(session.game_id.clone(), row.get::<_, String>(4)?);
+                    Ok((game, session))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(sessions)
+        })
+    }
+
+    /// Average playtime per day over a game's most recent `active_days`
+    /// days with any session
+    pub fn get_recent_active_day_average(
+        &self,
+        game_id: &str,
+        active_days: u32,
+    ) -> Result<Option<f64>> {
+        self.db.with_connection(|conn| {
+            let average = conn.query_row(
+                r#"
+                SELECT AVG(day_total)
+                FROM (
+                    SELECT
+                        DATE(date_time) as play_day,
+                        SUM(duration) as day_total
+                    FROM play_time
+                    WHERE game_id = ?1
+                    GROUP BY play_day
+                    ORDER BY play_day DESC
+                    LIMIT ?2
+                )
+                "#,
+                params![game_id, active_days],
+                |row| row.get(0),
+            )?;
+
+            Ok(average)
+        })
+    }
+
+    /// Get library-wide totals in a single round trip
+    pub fn get_library_totals(&self) -> Result<LibraryTotals> {
+        self.db.with_connection(|conn| {
+            let totals = conn.query_row(
+                r#"
+                SELECT
+                    (SELECT CAST(COALESCE(SUM(duration), 0) AS INTEGER) FROM play_time),
+                    (SELECT COUNT(*) FROM game_dict),
+                    (SELECT COUNT(*) FROM play_time),
+                    (SELECT COUNT(DISTINCT game_id) FROM play_time)
+                "#,
+                [],
+                |row| {
+                    Ok(LibraryTotals {
+                        total_secs: row.get(0)?,
+                        total_games: row.get(1)?,
+                        total_sessions: row.get(2)?,
+                        games_with_playtime: row.get(3)?,
+                    })
+                },
+            )?;
+
+            Ok(totals)
+        })
+    }
+
+    /// Get the distinct calendar days with at least one session, most recent
+    /// first
+    pub fn get_play_days(&self, game_id: Option<&str>) -> Result<Vec<NaiveDate>> {
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT DISTINCT DATE(date_time) as play_day
+                FROM play_time
+                WHERE ?1 IS NULL OR game_id = ?1
+                ORDER BY play_day DESC
+                "#,
+            )?;
+
+            let days = stmt
+                .query_map(params![game_id], |row| row.get::<_, String>(0))?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(days
+                .iter()
+                .filter_map(|day| NaiveDate::parse_from_str(day, "%Y-%m-%d").ok())
+                .collect())
+        })
+    }
+
+    /// Get per-game playtime totals for an inclusive date range, most played
+    /// first
+    pub fn get_range_summary(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<(Game, i64)>> {
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT g.game_id, g.name, CAST(SUM(pt.duration) AS INTEGER) as total_time
+                FROM play_time pt
+                JOIN game_dict g ON pt.game_id = g.game_id
+                WHERE DATE(pt.date_time) BETWEEN ?1 AND ?2
+                GROUP BY g.game_id
+                ORDER BY total_time DESC, g.name ASC
+                "#,
+            )?;
+
+            let summary = stmt
+                .query_map(
+                    params![start_date.to_string(), end_date.to_string()],
+                    |row| {
+                        Ok((
+                            Game::new(row.get::<_, String>(0)?, row.get::<_, String>(1)?),
+                            row.get(2)?,
+                        ))
+                    },
+                )?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(summary)
+        })
+    }
+
+    /// Average playtime per active day (days with any session), or `None`
+    /// when nothing was played
+    pub fn get_average_daily_playtime(&self, game_id: Option<&str>) -> Result<Option<f64>> {
+        self.db.with_connection(|conn| {
+            let average = conn.query_row(
+                r#"
+                SELECT
+                    CAST(SUM(duration) AS REAL)
+                        / COUNT(DISTINCT DATE(date_time))
+                FROM play_time
+                WHERE ?1 IS NULL OR game_id = ?1
+                "#,
+                params![game_id],
+                |row| row.get(0),
+            )?;
+
+            Ok(average)
+        })
+    }
+
+    /// Share of playtime (0.0-1.0) recorded with a `migrated` source
+    ///
+    /// Returns 0.0 when nothing was played.
+    pub fn get_migrated_fraction(&self, game_id: Option<&str>) -> Result<f64> {
+        self.db.with_connection(|conn| {
+            let (migrated, total): (f64, f64) = conn.query_row(
+                r#"
+                SELECT
+                    COALESCE(SUM(CASE WHEN migrated IS NOT NULL THEN duration END), 0.0),
+                    COALESCE(SUM(duration), 0.0)
+                FROM play_time
+                WHERE ?1 IS NULL OR game_id = ?1
+                "#,
+                params![game_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?;
+
+            if total <= 0.0 {
+                return Ok(0.0);
+            }
+
+            Ok(migrated / total)
+        })
+    }
+
+    /// Average number of sessions per active day, or `None` when nothing was
+    /// played
+    pub fn get_sessions_per_active_day(&self, game_id: Option<&str>) -> Result<Option<f64>> {
+        self.db.with_connection(|conn| {
+            let ratio = conn.query_row(
+                r#"
+                SELECT
+                    CAST(COUNT(*) AS REAL)
+                        / COUNT(DISTINCT DATE(date_time))
+                FROM play_time
+                WHERE ?1 IS NULL OR game_id = ?1
+                "#,
+                params![game_id],
+                |row| row.get(0),
+            )?;
+
+            Ok(ratio)
+        })
+    }
+
+    /// Get games with at least one session in an inclusive date range,
+    /// ordered by name
+    pub fn get_games_played_in_range(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<Game>> {
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT g.game_id, g.name
+                FROM game_dict g
+                WHERE EXISTS (
+                    SELECT 1
+                    FROM play_time pt
+                    WHERE pt.game_id = g.game_id
+                      AND DATE(pt.date_time) BETWEEN ?1 AND ?2
                 )
-                .optional()?;
+                ORDER BY g.name ASC
+                "#,
+            )?;
 
-            Ok(result)
+            let games = stmt
+                .query_map(
+                    params![start_date.to_string(), end_date.to_string()],
+                    |row| {
+                        Ok(Game::new(
+                            row.get::<_, String>(0)?,
+                            row.get::<_, String>(1)?,
+                        ))
+                    },
+                )?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(games)
         })
     }
+
+    /// Median session length for a game, or `None` if it has no sessions
+    pub fn get_median_session_length(&self, game_id: &str) -> Result<Option<f64>> {
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT duration as session_time
+                FROM play_time
+                WHERE game_id = ?1
+                ORDER BY session_time ASC
+                "#,
+            )?;
+
+            let durations = stmt
+                .query_map(params![game_id], |row| row.get::<_, f64>(0))?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            let mid = durations.len() / 2;
+
+            Ok(match durations.len() {
+                0 => None,
+                len if len % 2 == 1 => Some(durations[mid]),
+                _ => Some((durations[mid - 1] + durations[mid]) / 2.0),
+            })
+        })
+    }
+
+    /// Get the calendar day with the highest total playtime across all games
+    pub fn get_busiest_day(&self) -> Result<Option<(NaiveDate, i64)>> {
+        self.db.with_connection(|conn| {
+            let busiest = conn
+                .query_row(
+                    r#"
+                    SELECT
+                        DATE(date_time) as play_day,
+                        CAST(SUM(duration) AS INTEGER) as day_total
+                    FROM play_time
+                    GROUP BY play_day
+                    ORDER BY day_total DESC, play_day ASC
+                    LIMIT 1
+                    "#,
+                    [],
+                    |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)),
+                )
+                .optional()?;
+
+            Ok(busiest.and_then(|(day, total)| {
+                NaiveDate::parse_from_str(&day, "%Y-%m-%d")
+                    .ok()
+                    .map(|day| (day, total))
+            }))
+        })
+    }
+
+    /// Get the games with the most sessions, regardless of total time
+    pub fn get_top_games_by_sessions(&self, limit: usize) -> Result<Vec<GameStatistics>> {
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                r#"
+                SELECT
+                    g.game_id,
+                    g.name,
+                    CAST(SUM(pt.duration) AS INTEGER) as total_time,
+                    COUNT(pt.id) as total_sessions,
+                    MAX(pt.date_time) as last_played,
+                    {} as last_session_duration
+                FROM game_dict g
+                JOIN play_time pt ON g.game_id = pt.game_id
+                GROUP BY g.game_id, g.name
+                ORDER BY total_sessions DESC, total_time DESC
+                LIMIT ?1
+                "#,
+                LAST_SESSION_DURATION_SQL
+            ))?;
+
+            let stats = stmt
+                .query_map(params![limit as i64], game_statistics_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(stats)
+        })
+    }
+
+    /// Get the games with the most playtime between `start` and `end`
+    /// inclusive, capped at `limit`
+    ///
+    /// Totals, session counts and `last_played` only cover the window;
+    /// `last_session_duration` is the game's newest session overall.
+    pub fn get_top_games(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        limit: u32,
+    ) -> Result<Vec<GameStatistics>> {
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                r#"
+                SELECT
+                    g.game_id,
+                    g.name,
+                    CAST(SUM(pt.duration) AS INTEGER) as total_time,
+                    COUNT(pt.id) as total_sessions,
+                    MAX(pt.date_time) as last_played,
+                    {} as last_session_duration
+                FROM game_dict g
+                JOIN play_time pt ON g.game_id = pt.game_id
+                WHERE DATE(pt.date_time) BETWEEN ?1 AND ?2
+                GROUP BY g.game_id, g.name
+                ORDER BY total_time DESC, g.game_id ASC
+                LIMIT ?3
+                "#,
+                LAST_SESSION_DURATION_SQL
+            ))?;
+
+            let stats = stmt
+                .query_map(
+                    params![start.to_string(), end.to_string(), limit],
+                    game_statistics_from_row,
+                )?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(stats)
+        })
+    }
+
+    /// Get monthly playtime totals per game for a year, keyed by game id
+    ///
+    /// Index 0 is January.
+    pub fn get_monthly_per_game(&self, year: i32) -> Result<HashMap<String, [i64; 12]>> {
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT
+                    game_id,
+                    CAST(STRFTIME('%m', date_time) AS INTEGER) as month,
+                    CAST(SUM(duration) AS INTEGER) as month_total
+                FROM play_time
+                WHERE STRFTIME('%Y', date_time) = ?1
+                GROUP BY game_id, month
+                "#,
+            )?;
+
+            let rows = stmt.query_map(params![format!("{:04}", year)], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, u32>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            })?;
+
+            let mut monthly: HashMap<String, [i64; 12]> = HashMap::new();
+
+            for row in rows {
+                let (game_id, month, total) = row?;
+                monthly.entry(game_id).or_insert([0; 12])[month as usize - 1] = total;
+            }
+
+            Ok(monthly)
+        })
+    }
+
+    /// Attach a journal note to a day, replacing any existing note
+    pub fn set_day_note(&self, date: NaiveDate, note: &str) -> Result<()> {
+        self.db.with_connection(|conn| {
+            conn.execute(
+                "INSERT INTO day_note (date, note)
+                 VALUES (?1, ?2)
+                 ON CONFLICT(date) DO UPDATE SET note = ?2",
+                params![date.to_string(), note],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn get_day_note(&self, date: NaiveDate) -> Result<Option<String>> {
+        self.db.with_connection(|conn| {
+            let note = conn
+                .query_row(
+                    "SELECT note FROM day_note WHERE date = ?1",
+                    params![date.to_string()],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            Ok(note)
+        })
+    }
+
+    /// Get the earliest and latest session days, or `None` without sessions
+    pub fn get_activity_span(&self) -> Result<Option<(NaiveDate, NaiveDate)>> {
+        self.db.with_connection(|conn| {
+            let (first, last) = conn.query_row(
+                r#"
+                SELECT
+                    MIN(DATE(date_time)),
+                    MAX(DATE(date_time))
+                FROM play_time
+                "#,
+                [],
+                |row| {
+                    Ok((
+                        row.get::<_, Option<String>>(0)?,
+                        row.get::<_, Option<String>>(1)?,
+                    ))
+                },
+            )?;
+
+            let parse = |day: Option<String>| {
+                day.and_then(|day| NaiveDate::parse_from_str(&day, "%Y-%m-%d").ok())
+            };
+
+            Ok(parse(first).zip(parse(last)))
+        })
+    }
+
+    /// Wall-clock playtime for a game with overlapping sessions counted once
+    pub fn get_total_distinct_time(&self, game_id: &str) -> Result<i64> {
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT
+                    date_time as session_date,
+                    CAST(duration AS INTEGER) as session_time
+                FROM play_time
+                WHERE game_id = ?1
+                ORDER BY session_date ASC
+                "#,
+            )?;
+
+            let mut intervals = stmt
+                .query_map(params![game_id], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?
+                .into_iter()
+                .filter_map(|(date, time)| {
+                    let start = NaiveDateTime::parse_from_str(&date, "%Y-%m-%dT%H:%M:%S")
+                        .ok()?
+                        .and_utc()
+                        .timestamp();
+                    Some((start, start + time.max(0)))
+                })
+                .collect::<Vec<_>>();
+
+            intervals.sort_unstable();
+
+            let mut total = 0;
+            let mut covered_until = i64::MIN;
+
+            for (start, end) in intervals {
+                if end > covered_until {
+                    total += end - start.max(covered_until);
+                    covered_until = end;
+                }
+            }
+
+            Ok(total)
+        })
+    }
+
+    /// Get total playtime per day across all games for days with any
+    /// sessions, oldest first
+    ///
+    /// Days start at `day_start_hour` like
+    /// `get_daily_statistics_with_rollover`.
+    pub fn get_daily_totals(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        day_start_hour: u32,
+    ) -> Result<Vec<(NaiveDate, i64)>> {
+        if day_start_hour > 23 {
+            return Err(Error::InvalidInput(
+                "Day start hour must be between 0 and 23".into(),
+            ));
+        }
+
+        let day_shift = format!("-{} hours", day_start_hour);
+
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT
+                    DATE(date_time, ?3) as play_day,
+                    CAST(SUM(duration) AS INTEGER) as day_total
+                FROM play_time
+                WHERE DATE(date_time, ?3) BETWEEN ?1 AND ?2
+                GROUP BY play_day
+                ORDER BY play_day ASC
+                "#,
+            )?;
+
+            let totals = stmt
+                .query_map(
+                    params![start_date.to_string(), end_date.to_string(), day_shift],
+                    |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)),
+                )?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(totals
+                .into_iter()
+                .filter_map(|(day, total)| {
+                    NaiveDate::parse_from_str(&day, "%Y-%m-%d")
+                        .ok()
+                        .map(|day| (day, total))
+                })
+                .collect())
+        })
+    }
+
+    /// Per-game playtime grouped by calendar week, counting only sessions
+    /// dated within `[start_date, end_date]`
+    ///
+    /// Weeks are ordered oldest first; their bounds are clipped to the range.
+    pub fn get_weekly_statistics(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<WeeklyStatistics>> {
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT
+                    MIN(DATE(pt.date_time)) as first_day,
+                    g.game_id,
+                    g.name,
+                    CAST(SUM(pt.duration) AS INTEGER) as total_time
+                FROM play_time pt
+                JOIN game_dict g ON pt.game_id = g.game_id
+                WHERE DATE(pt.date_time) BETWEEN ?1 AND ?2
+                GROUP BY STRFTIME('%Y-%W', pt.date_time), g.game_id
+                "#,
+            )?;
+
+            let rows = stmt.query_map(
+                params![start_date.to_string(), end_date.to_string()],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, i64>(3)?,
+                    ))
+                },
+            )?;
+
+            // `%W` restarts at each new year, so a week spanning New Year
+            // comes back as two groups; merge them by their Monday
+            let mut weeks: BTreeMap<NaiveDate, Vec<WeeklyGameStats>> = BTreeMap::new();
+
+            for row in rows {
+                let (first_day, game_id, game_name, time) = row?;
+                let Ok(first_day) = NaiveDate::parse_from_str(&first_day, "%Y-%m-%d") else {
+                    continue;
+                };
+
+                let games = weeks.entry(start_of_week(first_day)).or_default();
+                match games.iter_mut().find(|stats| stats.game.id == game_id) {
+                    Some(stats) => stats.time += time,
+                    None => games.push(WeeklyGameStats {
+                        game: Game::new(game_id, game_name),
+                        time,
+                    }),
+                }
+            }
+
+            let weekly = weeks
+                .into_iter()
+                .map(|(monday, mut games)| {
+                    games.sort_by(|a, b| {
+                        b.time
+                            .cmp(&a.time)
+                            .then_with(|| a.game.name.cmp(&b.game.name))
+                    });
+
+                    WeeklyStatistics {
+                        start_date: monday.max(start_date),
+                        end_date: (monday + Days::new(6)).min(end_date),
+                        games,
+                    }
+                })
+                .collect();
+
+            Ok(weekly)
+        })
+    }
+
+    /// Total playtime per calendar year, oldest first, for years with any
+    /// sessions
+    pub fn get_yearly_totals(&self) -> Result<Vec<(i32, i64)>> {
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT
+                    CAST(STRFTIME('%Y', date_time) AS INTEGER) as year,
+                    CAST(SUM(duration) AS INTEGER) as year_total
+                FROM play_time
+                WHERE date_time IS NOT NULL
+                GROUP BY STRFTIME('%Y', date_time)
+                ORDER BY year ASC
+                "#,
+            )?;
+
+            let totals = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(totals)
+        })
+    }
+
+    /// Write every session as a CSV row with a header, oldest first
+    ///
+    /// Rows are written as they are read, so the export never holds the
+    /// whole table in memory.
+    pub fn export_sessions_csv<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT pt.game_id, g.name, pt.date_time, pt.duration, pt.migrated
+                FROM play_time pt
+                LEFT JOIN game_dict g ON pt.game_id = g.game_id
+                ORDER BY pt.date_time ASC, pt.id ASC
+                "#,
+            )?;
+
+            writeln!(writer, "game_id,game_name,date_time,duration,migrated")?;
+
+            let mut rows = stmt.query([])?;
+
+            while let Some(row) = rows.next()? {
+                let game_id: String = row.get(0)?;
+                let game_name: Option<String> = row.get(1)?;
+                let date_time: String = row.get(2)?;
+                let duration: f64 = row.get(3)?;
+                let migrated: Option<String> = row.get(4)?;
+
+                writeln!(
+                    writer,
+                    "{},{},{},{},{}",
+                    csv_field(&game_id),
+                    csv_field(game_name.as_deref().unwrap_or_default()),
+                    csv_field(&date_time),
+                    duration,
+                    csv_field(migrated.as_deref().unwrap_or_default())
+                )?;
+            }
+
+            writer.flush()?;
+
+            Ok(())
+        })
+    }
+
+    /// Get a game's sessions dated earlier than a session inserted before
+    /// them, in insertion order
+    ///
+    /// Row ids reflect insertion order, so these are the sessions recorded
+    /// after the device clock jumped backward.
+    pub fn get_clock_anomalies(&self, game_id: &str) -> Result<Vec<PlaySession>> {
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT pt.game_id, pt.date_time, pt.duration, pt.checksum
+                FROM play_time pt
+                WHERE pt.game_id = ?1
+                    AND pt.date_time < (
+                        SELECT MAX(prev.date_time)
+                        FROM play_time prev
+                        WHERE prev.game_id = pt.game_id AND prev.id < pt.id
+                    )
+                ORDER BY pt.id ASC
+                "#,
+            )?;
+
+            let sessions = stmt
+                .query_map(params![game_id], play_session_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(sessions)
+        })
+    }
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break
+fn csv_field(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Keep `%`/`_` as wildcards and backslash escapes, but make sure a trailing
+/// lone backslash can't break the `ESCAPE` clause.
+fn escape_like_pattern(pattern: &str) -> String {
+    let mut escaped = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(next) => {
+                    escaped.push('\\');
+                    escaped.push(next);
+                }
+                None => escaped.push_str("\\\\"),
+            },
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use chrono::{Local, TimeZone};
+
+    use super::*;
+    use crate::db::migrations::run_migrations;
+    use crate::db::{GamesDao, TimeTrackingDao};
+
+    fn setup_test_db() -> Arc<Database> {
+        let temp_dir = env::temp_dir();
+        let db_path = temp_dir.join(format!("test_statistics_{}.db", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::new(&db_path).unwrap());
+
+        db.with_connection(run_migrations).unwrap();
+
+        db
+    }
+
+    fn insert_session(db: &Database, game_id: &str, date: &str, time: i64) {
+        db.with_connection(|conn| {
+            conn.execute(
+                "INSERT OR IGNORE INTO game_dict (game_id, name) VALUES (?1, ?1)",
+                params![game_id],
+            )?;
+            conn.execute(
+                "INSERT INTO play_time (game_id, date_time, duration) VALUES (?1, ?2, ?3)",
+                params![game_id, date, time],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_statistics_on_migrated_schema() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        let noon = Local.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        TimeTrackingDao::new(Arc::clone(&db))
+            .add_time(
+                "123",
+                "Test Game",
+                noon.timestamp() as f64,
+                noon.timestamp() as f64 + 1800.0,
+                None,
+            )
+            .unwrap();
+
+        let overall = dao.get_overall_statistics().unwrap();
+        assert_eq!(overall.len(), 1);
+        assert_eq!(overall[0].total_time, 1800);
+        assert_eq!(overall[0].total_sessions, 1);
+
+        let day = noon.date_naive();
+        let daily = dao.get_daily_statistics(day, day).unwrap();
+        assert_eq!(daily.len(), 1);
+        assert_eq!(daily[0].games[0].time, 1800);
+        assert_eq!(daily[0].games[0].sessions.len(), 1);
+    }
+
+    #[test]
+    fn test_session_length_histogram() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        for time in [120, 300, 900, 2400, 3600, 5000, 10_000] {
+            insert_session(&db, "123", "2024-01-01T10:00:00", time);
+        }
+        insert_session(&db, "456", "2024-01-01T10:00:00", 60);
+
+        let buckets = [300, 1800, 3600, 7200];
+
+        let counts = dao
+            .get_session_length_histogram(Some("123"), &buckets)
+            .unwrap();
+        assert_eq!(counts, vec![2, 1, 2, 1, 1]);
+
+        let counts = dao.get_session_length_histogram(None, &buckets).unwrap();
+        assert_eq!(counts, vec![3, 1, 2, 1, 1]);
+
+        assert!(dao.get_session_length_histogram(None, &[600, 300]).is_err());
+    }
+
+    #[test]
+    fn test_snapshots() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        insert_session(&db, "123", "2024-01-01T10:00:00", 3600);
+        dao.take_snapshot().unwrap();
+
+        insert_session(&db, "456", "2024-01-02T10:00:00", 1800);
+        dao.take_snapshot().unwrap();
+
+        let today = chrono::Local::now().date_naive();
+        let snapshots = dao.get_snapshots(today, today).unwrap();
+
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].total_time, 3600);
+        assert_eq!(snapshots[0].game_count, 1);
+        assert!(snapshots[1].total_time > snapshots[0].total_time);
+        assert_eq!(snapshots[1].game_count, 2);
+    }
+
+    #[test]
+    fn test_last_session_duration() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        // Inserted out of order: the later day wins, not the later row
+        insert_session(&db, "123", "2024-01-02T10:00:00", 900);
+        insert_session(&db, "123", "2024-01-01T10:00:00", 3600);
+
+        let stats = dao.get_game_statistics("123").unwrap().unwrap();
+        assert_eq!(stats.last_session_duration, Some(900));
+
+        let stats = GamesDao::new(Arc::clone(&db))
+            .get_game_with_stats("123")
+            .unwrap()
+            .unwrap();
+        assert_eq!(stats.last_session_duration, Some(900));
+
+        assert_eq!(
+            dao.get_overall_statistics().unwrap()[0].last_session_duration,
+            Some(900)
+        );
+        assert_eq!(
+            dao.get_top_games_by_sessions(1).unwrap()[0].last_session_duration,
+            Some(900)
+        );
+
+        let first_day = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let top = dao.get_top_games(first_day, first_day, 1).unwrap();
+        assert_eq!(top[0].last_session_duration, Some(900));
+    }
+
+    #[test]
+    fn test_milestone_date() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        insert_session(&db, "123", "2024-01-10T10:00:00", 3600);
+        insert_session(&db, "123", "2024-01-01T10:00:00", 3600);
+        insert_session(&db, "123", "2024-01-05T22:00:00", 3600);
+
+        assert_eq!(
+            dao.get_milestone_date("123", 7200).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 1, 5)
+        );
+        assert_eq!(
+            dao.get_milestone_date("123", 3601).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 1, 5)
+        );
+        assert_eq!(dao.get_milestone_date("123", 100_000).unwrap(), None);
+    }
+
+    #[test]
+    fn test_last_played_map() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        insert_session(&db, "123", "2024-01-01T10:00:00", 3600);
+        insert_session(&db, "123", "2024-01-03T18:30:00", 600);
+        insert_session(&db, "456", "2024-02-01T09:15:00", 1800);
+
+        let parse = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").unwrap();
+
+        let all = dao.get_last_played_map(&[]).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all["123"], parse("2024-01-03T18:30:00"));
+        assert_eq!(all["456"], parse("2024-02-01T09:15:00"));
+
+        let filtered = dao.get_last_played_map(&["456", "789"]).unwrap();
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered["456"], parse("2024-02-01T09:15:00"));
+    }
+
+    #[test]
+    fn test_adjustment_summary() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+        let time_tracking = TimeTrackingDao::new(Arc::clone(&db));
+
+        let noon = chrono::Local
+            .with_ymd_and_hms(2024, 3, 1, 12, 0, 0)
+            .unwrap()
+            .timestamp() as f64;
+
+        time_tracking
+            .add_time_with_idle("123", "Test Game", noon, noon + 3600.0, 900.0, None)
+            .unwrap();
+        insert_session(&db, "123", "2024-03-02T10:00:00", 600);
+
+        let summary = dao.get_adjustment_summary("123").unwrap();
+
+        assert_eq!(summary.adjusted_time, 2700 + 600);
+        assert_eq!(summary.raw_time, 3600 + 600);
+        assert!(summary.raw_time > summary.adjusted_time);
+        assert_eq!(summary.trimmed(), 900);
+    }
+
+    #[test]
+    fn test_daily_statistics_day_rollover() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        insert_session(&db, "123", "2024-01-02T02:00:00", 1800);
+        insert_session(&db, "123", "2024-01-02T12:00:00", 600);
+
+        let jan_1 = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let jan_2 = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+
+        let midnight = dao.get_daily_statistics(jan_1, jan_2).unwrap();
+        assert_eq!(midnight.len(), 1);
+        assert_eq!(midnight[0].date, jan_2);
+        assert_eq!(midnight[0].games[0].time, 2400);
+
+        let rollover = dao
+            .get_daily_statistics_with_rollover(jan_1, jan_2, 4)
+            .unwrap();
+        assert_eq!(rollover.len(), 2);
+        assert_eq!(rollover[0].date, jan_2);
+        assert_eq!(rollover[0].games[0].time, 600);
+        assert_eq!(rollover[1].date, jan_1);
+        assert_eq!(rollover[1].games[0].time, 1800);
+
+        assert!(
+            dao.get_daily_statistics_with_rollover(jan_1, jan_2, 24)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_total_for_name_pattern() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        let games = [
+            ("1", "Final Fantasy VII", 3600),
+            ("2", "Final Fantasy X", 1800),
+            ("3", "Dragon Quest XI", 7200),
+            ("4", "100% Orange Juice", 60),
+        ];
+
+        for (id, name, time) in games {
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO game_dict (game_id, name) VALUES (?1, ?2)",
+                    params![id, name],
+                )?;
+                Ok(())
+            })
+            .unwrap();
+            insert_session(&db, id, "2024-01-01T10:00:00", time);
+        }
+
+        assert_eq!(
+            dao.get_total_for_name_pattern("Final Fantasy %").unwrap(),
+            5400
+        );
+        assert_eq!(dao.get_total_for_name_pattern("Dragon%").unwrap(), 7200);
+        assert_eq!(dao.get_total_for_name_pattern("100\\%%").unwrap(), 60);
+        assert_eq!(dao.get_total_for_name_pattern("Zelda%").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_sessions_for_day() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        insert_session(&db, "123", "2024-01-01T20:00:00", 600);
+        insert_session(&db, "456", "2024-01-01T08:00:00", 1200);
+        insert_session(&db, "123", "2024-01-01T12:30:00", 300);
+        insert_session(&db, "456", "2024-01-02T09:00:00", 900);
+
+        let sessions = dao
+            .get_sessions_for_day(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap())
+            .unwrap();
+
+        let order: Vec<(&str, f64)> = sessions
+            .iter()
+            .map(|(game, session)| (game.id.as_str(), session.duration))
+            .collect();
+        assert_eq!(order, vec![("456", 1200.0), ("123", 300.0), ("123", 600.0)]);
+        assert!(
+            sessions
+                .windows(2)
+                .all(|w| w[0].1.started_at <= w[1].1.started_at)
+        );
+    }
+
+    #[test]
+    fn test_sessions_on_date() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        insert_session(&db, "789", "2024-01-01T21:15:00", 450);
+        insert_session(&db, "123", "2024-01-01T20:00:00", 600);
+        insert_session(&db, "456", "2024-01-01T08:00:00", 1200);
+        insert_session(&db, "123", "2024-01-02T09:00:00", 900);
+        db.with_connection(|conn| {
+            conn.execute(
+                "UPDATE play_time SET migrated = 'manual' WHERE game_id = '456'",
+                [],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        let sessions = dao
+            .get_sessions_on_date(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap())
+            .unwrap();
+
+        let timeline: Vec<(&str, String)> = sessions
+            .iter()
+            .map(|(game, session)| (game.id.as_str(), session.date.time().to_string()))
+            .collect();
+        assert_eq!(
+            timeline,
+            vec![
+                ("456", "08:00:00".to_string()),
+                ("123", "20:00:00".to_string()),
+                ("789", "21:15:00".to_string())
+            ]
+        );
+        assert_eq!(sessions[0].1.migrated.as_deref(), Some("manual"));
+        assert_eq!(sessions[2].1.duration, 450.0);
+    }
+
+    #[test]
+    fn test_library_totals() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        insert_session(&db, "123", "2024-01-01T10:00:00", 600);
+        insert_session(&db, "123", "2024-01-02T10:00:00", 900);
+        insert_session(&db, "456", "2024-01-03T10:00:00", 1200);
+        db.with_connection(|conn| {
+            conn.execute(
+                "INSERT INTO game_dict (game_id, name) VALUES ('789', 'Unplayed')",
+                [],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(
+            dao.get_library_totals().unwrap(),
+            LibraryTotals {
+                total_secs: 2700,
+                total_games: 3,
+                total_sessions: 3,
+                games_with_playtime: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_play_days() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        insert_session(&db, "123", "2024-01-01T10:00:00", 600);
+        insert_session(&db, "123", "2024-01-01T20:00:00", 600);
+        insert_session(&db, "123", "2024-01-03T10:00:00", 600);
+        insert_session(&db, "456", "2024-01-02T10:00:00", 600);
+
+        let day = |d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+        assert_eq!(
+            dao.get_play_days(Some("123")).unwrap(),
+            vec![day(3), day(1)]
+        );
+        assert_eq!(
+            dao.get_play_days(None).unwrap(),
+            vec![day(3), day(2), day(1)]
+        );
+    }
+
+    #[test]
+    fn test_average_daily_playtime() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        insert_session(&db, "123", "2024-01-01T10:00:00", 3600);
+        insert_session(&db, "123", "2024-01-01T20:00:00", 1800);
+        insert_session(&db, "123", "2024-01-05T10:00:00", 1800);
+        insert_session(&db, "456", "2024-01-03T10:00:00", 900);
+
+        assert_eq!(
+            dao.get_average_daily_playtime(Some("123")).unwrap(),
+            Some(3600.0)
+        );
+        assert_eq!(dao.get_average_daily_playtime(None).unwrap(), Some(2700.0));
+        assert_eq!(dao.get_average_daily_playtime(Some("789")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_games_played_in_range() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        db.with_connection(|conn| {
+            conn.execute_batch(
+                r#"
+                INSERT INTO game_dict (game_id, name)
+                VALUES ('1', 'Zelda'), ('2', 'Celeste'), ('3', 'Hades');
+                "#,
+            )?;
+            Ok(())
+        })
+        .unwrap();
+        insert_session(&db, "1", "2024-03-01T10:00:00", 600);
+        insert_session(&db, "1", "2024-03-15T10:00:00", 600);
+        insert_session(&db, "2", "2024-03-31T23:00:00", 600);
+        insert_session(&db, "3", "2024-02-29T23:00:00", 600);
+        insert_session(&db, "3", "2024-04-01T00:00:00", 600);
+
+        let games = dao
+            .get_games_played_in_range(
+                NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+            )
+            .unwrap();
+
+        let names: Vec<&str> = games.iter().map(|game| game.name.as_str()).collect();
+        assert_eq!(names, vec!["Celeste", "Zelda"]);
+    }
+
+    #[test]
+    fn test_median_session_length() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        for time in [300, 36000, 600] {
+            insert_session(&db, "123", "2024-01-01T10:00:00", time);
+        }
+        for time in [900, 100, 36000, 300] {
+            insert_session(&db, "456", "2024-01-01T10:00:00", time);
+        }
+
+        assert_eq!(dao.get_median_session_length("123").unwrap(), Some(600.0));
+        assert_eq!(dao.get_median_session_length("456").unwrap(), Some(600.0));
+        assert_eq!(dao.get_median_session_length("789").unwrap(), None);
+    }
+
+    #[test]
+    fn test_top_games_by_sessions() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        insert_session(&db, "long", "2024-01-01T10:00:00", 36000);
+        for hour in 10..15 {
+            insert_session(&db, "short", &format!("2024-01-02T{}:00:00", hour), 120);
+        }
+        insert_session(&db, "mid", "2024-01-03T10:00:00", 600);
+        insert_session(&db, "mid", "2024-01-04T10:00:00", 600);
+
+        let top = dao.get_top_games_by_sessions(2).unwrap();
+
+        let ranked: Vec<(&str, i64)> = top
+            .iter()
+            .map(|stats| (stats.game.id.as_str(), stats.total_sessions))
+            .collect();
+        assert_eq!(ranked, vec![("short", 5), ("mid", 2)]);
+        assert_eq!(top[0].total_time, 600);
+    }
+
+    #[test]
+    fn test_top_games_in_window() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        insert_session(&db, "1", "2024-03-02T10:00:00", 1800);
+        insert_session(&db, "1", "2024-03-20T10:00:00", 1800);
+        insert_session(&db, "1", "2024-04-02T10:00:00", 9000);
+        insert_session(&db, "2", "2024-03-15T10:00:00", 7200);
+        insert_session(&db, "3", "2024-03-31T22:00:00", 600);
+        insert_session(&db, "4", "2024-02-29T10:00:00", 90_000);
+
+        let march = (
+            NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+        );
+
+        let top = dao.get_top_games(march.0, march.1, 10).unwrap();
+        let ranked: Vec<(&str, i64, i64)> = top
+            .iter()
+            .map(|stats| {
+                (
+                    stats.game.id.as_str(),
+                    stats.total_time,
+                    stats.total_sessions,
+                )
+            })
+            .collect();
+        assert_eq!(ranked, vec![("2", 7200, 1), ("1", 3600, 2), ("3", 600, 1)]);
+        assert_eq!(
+            top[1].last_played.unwrap().to_string(),
+            "2024-03-20 10:00:00"
+        );
+
+        assert_eq!(dao.get_top_games(march.0, march.1, 2).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_monthly_per_game() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        insert_session(&db, "123", "2024-02-10T10:00:00", 600);
+        insert_session(&db, "123", "2024-02-20T10:00:00", 300);
+        insert_session(&db, "123", "2024-11-05T10:00:00", 1200);
+        insert_session(&db, "123", "2023-11-05T10:00:00", 5000);
+
+        let monthly = dao.get_monthly_per_game(2024).unwrap();
+
+        let mut expected = [0; 12];
+        expected[1] = 900;
+        expected[10] = 1200;
+        assert_eq!(monthly.len(), 1);
+        assert_eq!(monthly["123"], expected);
+        assert!(dao.get_monthly_per_game(2022).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_day_note() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+        let day = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let next_day = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+
+        assert_eq!(dao.get_day_note(day).unwrap(), None);
+
+        dao.set_day_note(day, "Started a new save").unwrap();
+        dao.set_day_note(day, "Beat the first boss").unwrap();
+        assert_eq!(
+            dao.get_day_note(day).unwrap().as_deref(),
+            Some("Beat the first boss")
+        );
+
+        insert_session(&db, "123", "2024-01-01T10:00:00", 600);
+        insert_session(&db, "123", "2024-01-02T10:00:00", 600);
+
+        let daily = dao.get_daily_statistics(day, next_day).unwrap();
+        assert_eq!(daily.len(), 2);
+        assert_eq!(daily[0].date, next_day);
+        assert_eq!(daily[0].note, None);
+        assert_eq!(daily[1].note.as_deref(), Some("Beat the first boss"));
+    }
+
+    #[test]
+    fn test_total_distinct_time() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        insert_session(&db, "123", "2024-01-01T10:00:00", 3600);
+        insert_session(&db, "123", "2024-01-01T10:30:00", 3600);
+        insert_session(&db, "123", "2024-01-01T10:45:00", 600);
+        insert_session(&db, "123", "2024-01-01T14:00:00", 600);
+
+        // 10:00-11:30 merged plus the separate 14:00 session
+        assert_eq!(dao.get_total_distinct_time("123").unwrap(), 5400 + 600);
+        assert!(dao.get_total_distinct_time("123").unwrap() < 3600 + 3600 + 600 + 600);
+        assert_eq!(dao.get_total_distinct_time("456").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_daily_totals() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        insert_session(&db, "123", "2024-01-01T10:00:00", 600);
+        insert_session(&db, "456", "2024-01-01T20:00:00", 300);
+        insert_session(&db, "123", "2024-01-03T02:00:00", 900);
+
+        let day = |d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+
+        assert_eq!(
+            dao.get_daily_totals(day(1), day(3), 0).unwrap(),
+            vec![(day(1), 900), (day(3), 900)]
+        );
+        assert_eq!(
+            dao.get_daily_totals(day(1), day(3), 4).unwrap(),
+            vec![(day(1), 900), (day(2), 900)]
+        );
+    }
+
+    #[test]
+    fn test_migrated_fraction() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        assert_eq!(dao.get_migrated_fraction(None).unwrap(), 0.0);
+
+        insert_session(&db, "123", "2024-03-01T10:00:00", 500);
+        insert_session(&db, "123", "2024-03-02T10:00:00", 300);
+        insert_session(&db, "123", "2024-03-03T10:00:00", 200);
+        insert_session(&db, "456", "2024-03-03T12:00:00", 1000);
+        db.with_connection(|conn| {
+            conn.execute(
+                "UPDATE play_time SET migrated = 'legacy' WHERE duration IN (500, 1000)",
+                [],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(dao.get_migrated_fraction(Some("123")).unwrap(), 0.5);
+        assert_eq!(dao.get_migrated_fraction(None).unwrap(), 0.75);
+        assert_eq!(dao.get_migrated_fraction(Some("789")).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_yearly_totals() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        assert!(dao.get_yearly_totals().unwrap().is_empty());
+
+        insert_session(&db, "123", "2024-02-10T10:00:00", 600);
+        insert_session(&db, "456", "2024-12-31T23:00:00", 300);
+        insert_session(&db, "123", "2022-06-01T10:00:00", 1200);
+        insert_session(&db, "123", "2023-01-01T00:30:00", 5000);
+
+        assert_eq!(
+            dao.get_yearly_totals().unwrap(),
+            vec![(2022, 1200), (2023, 5000), (2024, 900)]
+        );
+    }
+
+    #[test]
+    fn test_clock_anomalies() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        insert_session(&db, "123", "2024-03-01T10:00:00", 600);
+        insert_session(&db, "123", "2024-03-02T10:00:00", 600);
+        assert!(dao.get_clock_anomalies("123").unwrap().is_empty());
+
+        // Clock reset: inserted later but dated before both previous sessions
+        insert_session(&db, "123", "2024-01-01T10:00:00", 300);
+        insert_session(&db, "123", "2024-03-03T10:00:00", 600);
+        insert_session(&db, "456", "2023-01-01T10:00:00", 600);
+
+        let anomalies = dao.get_clock_anomalies("123").unwrap();
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].duration, 300.0);
+        assert!(dao.get_clock_anomalies("456").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_export_sessions_csv() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        insert_session(&db, "123", "2024-03-02T10:00:00", 600);
+        insert_session(&db, "456", "2024-03-01T09:30:00", 1200);
+        db.with_connection(|conn| {
+            conn.execute(
+                "UPDATE game_dict SET name = 'Dig, Dig \"Deluxe\"' WHERE game_id = '456'",
+                [],
+            )?;
+            conn.execute(
+                "UPDATE play_time SET migrated = 'manual' WHERE game_id = '456'",
+                [],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        let mut out = Vec::new();
+        dao.export_sessions_csv(&mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "game_id,game_name,date_time,duration,migrated\n\
+             456,\"Dig, Dig \"\"Deluxe\"\"\",2024-03-01T09:30:00,1200,manual\n\
+             123,123,2024-03-02T10:00:00,600,\n"
+        );
+    }
+
+    #[test]
+    fn test_reads_tolerate_fractional_durations() {
+        let db = setup_test_db();
+        let dao = StatisticsDao::new(Arc::clone(&db));
+
+        let start = Local.with_ymd_and_hms(2024, 3, 1, 23, 0, 0).unwrap();
+        TimeTrackingDao::new(Arc::clone(&db))
+            .add_time_with_idle(
+                "123",
+                "Test Game",
+                start.timestamp() as f64 + 0.4,
+                start.timestamp() as f64 + 7200.4,
+                1000.0,
+                None,
+            )
+            .unwrap();
+        // Databases written before whole-second storage may hold REAL rows
+        db.with_connection(|conn| {
+            conn.execute("UPDATE play_time SET duration = duration + 0.4", [])?;
+            conn.execute("UPDATE overall_time SET duration = duration + 0.8", [])?;
+            Ok(())
+        })
+        .unwrap();
+
+        let first = start.date_naive();
+        let last = first.succ_opt().unwrap();
+
+        assert_eq!(dao.get_overall_statistics().unwrap().len(), 1);
+        assert!(dao.get_game_statistics("123").unwrap().is_some());
+        dao.get_daily_statistics(first, last).unwrap();
+        dao.get_daily_statistics_with_rollover(first, last, 4)
+            .unwrap();
+        dao.get_session_length_histogram(None, &[300, 3600])
+            .unwrap();
+        dao.take_snapshot().unwrap();
+        dao.get_snapshots(first, last).unwrap();
+        dao.get_milestone_date("123", 60).unwrap();
+        dao.get_adjustment_summary("123").unwrap();
+        dao.get_total_for_name_pattern("Test%").unwrap();
+        dao.get_sessions_for_day(first).unwrap();
+        dao.get_longest_session_per_game().unwrap();
+        dao.get_recent_active_day_average("123", 7).unwrap();
+        dao.get_library_totals().unwrap();
+        dao.get_range_summary(first, last).unwrap();
+        dao.get_average_daily_playtime(None).unwrap();
+        dao.get_median_session_length("123").unwrap();
+        assert!(dao.get_busiest_day().unwrap().is_some());
+        dao.get_top_games_by_sessions(5).unwrap();
+        dao.get_top_games(first, last, 5).unwrap();
+        dao.get_monthly_per_game(2024).unwrap();
+        dao.get_total_distinct_time("123").unwrap();
+        dao.get_daily_totals(first, last, 0).unwrap();
+        dao.get_weekly_statistics(first, last).unwrap();
+        dao.get_yearly_totals().unwrap();
+        dao.export_sessions_csv(&mut Vec::new()).unwrap();
+        dao.get_clock_anomalies("123").unwrap();
+
+        let games = GamesDao::new(Arc::clone(&db));
+        assert!(games.get_game_with_stats("123").unwrap().is_some());
+        assert_eq!(games.get_all_games_with_stats().unwrap().len(), 1);
+        TimeTrackingDao::new(Arc::clone(&db))
+            .get_total_playtime("123")
+            .unwrap();
+    }
 }
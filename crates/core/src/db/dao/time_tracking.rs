@@ -1,23 +1,62 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
-use chrono::{Local, NaiveDateTime};
-use rusqlite::params;
+use chrono::{FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use rusqlite::{OptionalExtension, params};
 
 use crate::db::Database;
+use crate::db::dao::play_session_from_row;
 use crate::error::{Error, Result};
-use crate::models::PlaySession;
-use crate::utils::time::split_session_by_day;
+use crate::models::{DayAttribution, Game, ImportSession, PlaySession, TimeEntry};
+use crate::utils::time::split_session_by_day_in;
+
+/// Longest session `add_time` accepts unless overridden
+const DEFAULT_MAX_SPAN_DAYS: u32 = 366;
 
 #[derive(Clone)]
 pub struct TimeTrackingDao {
     db: Arc<Database>,
+    attribution: DayAttribution,
+    utc_offset: Option<FixedOffset>,
+    max_span_days: u32,
 }
 
 impl TimeTrackingDao {
     pub fn new(db: Arc<Database>) -> Self {
-        Self { db }
+        Self {
+            db,
+            attribution: DayAttribution::StartDay,
+            utc_offset: None,
+            max_span_days: DEFAULT_MAX_SPAN_DAYS,
+        }
+    }
+
+    /// Record sessions that cross a single midnight as one row on the chosen
+    /// day instead of splitting them
+    pub fn with_day_attribution(mut self, attribution: DayAttribution) -> Self {
+        self.attribution = attribution;
+
+        self
+    }
+
+    /// Find day boundaries at midnight in a fixed UTC offset instead of the
+    /// system's local timezone
+    pub fn with_utc_offset(mut self, offset: FixedOffset) -> Self {
+        self.utc_offset = Some(offset);
+
+        self
     }
 
+    /// Reject sessions spanning more than `days` days instead of splitting
+    /// them into one row per day
+    pub fn with_max_span_days(mut self, days: u32) -> Self {
+        self.max_span_days = days;
+
+        self
+    }
+
+    /// Add playtime, returning the number of rows stored after splitting it
+    /// at midnight
     pub fn add_time(
         &self,
         game_id: &str,
@@ -25,191 +64,1966 @@ impl TimeTrackingDao {
         started_at: f64,
         ended_at: f64,
         source: Option<&str>,
-    ) -> Result<()> {
+    ) -> Result<usize> {
+        let recorded =
+            self.add_time_with_idle(game_id, game_name, started_at, ended_at, 0.0, source)?;
+
+        Ok(recorded.len())
+    }
+
+    /// Add playtime with `idle_seconds` subtracted from the wall-clock span.
+    ///
+    /// The idle time is spread proportionally over multi-day fragments. Each
+    /// row stores the adjusted time in `duration` and the original span in
+    /// `raw_duration`. Returns the stored sessions with their recorded
+    /// durations.
+    pub fn add_time_with_idle(
+        &self,
+        game_id: &str,
+        game_name: &str,
+        started_at: f64,
+        ended_at: f64,
+        idle_seconds: f64,
+        source: Option<&str>,
+    ) -> Result<Vec<PlaySession>> {
+        let (rows, active_ratio) =
+            self.prepare_sessions(game_id, started_at, ended_at, idle_seconds)?;
+
+        self.db.transaction(|tx| {
+            record_sessions(tx, game_id, game_name, rows, active_ratio, source, None)
+        })
+    }
+
+    /// Add many play spans in a single transaction
+    ///
+    /// Each entry is validated and split at midnight like `add_time`, but
+    /// `game_dict` and `overall_time` are written once per game. If any entry
+    /// is invalid nothing is stored and the error lists every bad entry.
+    ///
+    /// With `defer_totals`, the totals of the games in the batch are rebuilt
+    /// from their sessions once at the end instead of being bumped, which is
+    /// faster for large imports.
+    pub fn add_time_batch(&self, entries: &[TimeEntry], defer_totals: bool) -> Result<()> {
+        self.record_time_batch(entries, defer_totals)?;
+
+        Ok(())
+    }
+
+    /// `add_time_batch`, returning the stored sessions
+    pub(crate) fn record_time_batch(
+        &self,
+        entries: &[TimeEntry],
+        defer_totals: bool,
+    ) -> Result<Vec<PlaySession>> {
+        let mut prepared = Vec::with_capacity(entries.len());
+        let mut invalid = Vec::new();
+
+        for (index, entry) in entries.iter().enumerate() {
+            match self.prepare_sessions(&entry.game_id, entry.started_at, entry.ended_at, 0.0) {
+                Ok(rows) => prepared.push(rows),
+                Err(err) => invalid.push(format!("#{} ({}): {}", index, entry.game_id, err)),
+            }
+        }
+
+        if !invalid.is_empty() {
+            return Err(Error::InvalidInput(format!(
+                "{} of {} entries are invalid: {}",
+                invalid.len(),
+                entries.len(),
+                invalid.join("; ")
+            )));
+        }
+
+        // Later entries win the name, as with repeated `add_time` calls
+        let names: BTreeMap<&str, &str> = entries
+            .iter()
+            .map(|entry| (entry.game_id.as_str(), entry.game_name.as_str()))
+            .collect();
+
+        self.db.transaction(|tx| {
+            for (game_id, game_name) in &names {
+                upsert_game_name(tx, game_id, game_name)?;
+            }
+
+            let mut totals: BTreeMap<&str, i64> = BTreeMap::new();
+            let mut recorded = Vec::new();
+
+            for (entry, (rows, active_ratio)) in entries.iter().zip(prepared) {
+                let sessions =
+                    insert_session_rows(tx, rows, active_ratio, entry.source.as_deref(), None)?;
+
+                *totals.entry(&entry.game_id).or_default() += sessions
+                    .iter()
+                    .map(|session| session.duration as i64)
+                    .sum::<i64>();
+                recorded.extend(sessions);
+            }
+
+            if defer_totals {
+                for game_id in names.keys() {
+                    rebuild_overall_time(tx, Some(game_id))?;
+                }
+            } else {
+                for (game_id, total) in totals {
+                    add_to_overall_time(tx, game_id, total)?;
+                }
+            }
+
+            Ok(recorded)
+        })
+    }
+
+    /// Add playtime at most once per `idempotency_key`
+    ///
+    /// Returns the number of rows recorded under the key and the sessions
+    /// stored by this call. Retrying with a key that was already applied is
+    /// a no-op that stores nothing and returns the first call's row count.
+    /// Without a key this behaves like `add_time`.
+    pub fn add_time_with_key(
+        &self,
+        game_id: &str,
+        game_name: &str,
+        started_at: f64,
+        ended_at: f64,
+        source: Option<&str>,
+        idempotency_key: Option<&str>,
+    ) -> Result<(usize, Vec<PlaySession>)> {
+        let (rows, active_ratio) = self.prepare_sessions(game_id, started_at, ended_at, 0.0)?;
+        let now = Local::now().naive_local();
+
+        self.db.transaction(|tx| {
+            let Some(key) = idempotency_key else {
+                let recorded =
+                    record_sessions(tx, game_id, game_name, rows, active_ratio, source, None)?;
+                return Ok((recorded.len(), recorded));
+            };
+
+            let applied: Option<i64> = tx
+                .query_row(
+                    "SELECT row_count FROM applied_keys WHERE key = ?1",
+                    params![key],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            if let Some(row_count) = applied {
+                return Ok((row_count as usize, Vec::new()));
+            }
+
+            let recorded =
+                record_sessions(tx, game_id, game_name, rows, active_ratio, source, None)?;
+
+            tx.execute(
+                "INSERT INTO applied_keys (key, applied_at, row_count) VALUES (?1, ?2, ?3)",
+                params![
+                    key,
+                    now.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                    recorded.len() as i64
+                ],
+            )?;
+
+            Ok((recorded.len(), recorded))
+        })
+    }
+
+    /// Add playtime at most once per `(game_id, checksum)`
+    ///
+    /// The checksum is stored on the first row of the session. Recording a
+    /// session whose checksum the game already has is a no-op and returns no
+    /// sessions.
+    pub fn add_time_with_checksum(
+        &self,
+        game_id: &str,
+        game_name: &str,
+        started_at: f64,
+        ended_at: f64,
+        source: Option<&str>,
+        checksum: &str,
+    ) -> Result<Vec<PlaySession>> {
+        let (rows, active_ratio) = self.prepare_sessions(game_id, started_at, ended_at, 0.0)?;
+
+        self.db.transaction(|tx| {
+            record_sessions(
+                tx,
+                game_id,
+                game_name,
+                rows,
+                active_ratio,
+                source,
+                Some(checksum),
+            )
+        })
+    }
+
+    /// Validate a session span and split it into the rows to store, each
+    /// with the date it is recorded under, along with the share of each row
+    /// that was active
+    fn prepare_sessions(
+        &self,
+        game_id: &str,
+        started_at: f64,
+        ended_at: f64,
+        idle_seconds: f64,
+    ) -> Result<(Vec<(NaiveDateTime, PlaySession)>, f64)> {
+        if !started_at.is_finite() || !ended_at.is_finite() {
+            return Err(Error::InvalidInput(
+                "Start and end times must be finite".into(),
+            ));
+        }
+
         if ended_at <= started_at {
             return Err(Error::InvalidInput(
                 "End time must be after start time".into(),
             ));
         }
 
+        if ended_at - started_at >= i64::MAX as f64 {
+            return Err(Error::InvalidInput("Session is too long".into()));
+        }
+
+        if ended_at - started_at > f64::from(self.max_span_days) * 86_400.0 {
+            return Err(Error::InvalidInput(format!(
+                "Session spans more than {} days",
+                self.max_span_days
+            )));
+        }
+
+        if !(0.0..=ended_at - started_at).contains(&idle_seconds) {
+            return Err(Error::InvalidInput(
+                "Idle time must be between zero and the session length".into(),
+            ));
+        }
+
         let session = PlaySession::new(game_id.to_string(), started_at, ended_at);
+        let active_ratio = 1.0 - idle_seconds / session.duration;
 
-        let sessions = if session.is_multi_day() {
-            split_session_by_day(&session)
-        } else {
-            vec![session]
+        let rows = match self.utc_offset {
+            Some(offset) => day_rows_in(&offset, self.attribution, session),
+            None => day_rows_in(&Local, self.attribution, session),
         };
 
+        Ok((rows, active_ratio))
+    }
+
+    /// Record a manual correction as its own session and add it to
+    /// `overall_time`
+    pub fn apply_manual_time_correction(
+        &self,
+        game_id: &str,
+        game_name: &str,
+        time_seconds: i64,
+        source: &str,
+    ) -> Result<()> {
+        let now = Local::now().naive_local();
+
         self.db.transaction(|tx| {
             tx.execute(
                 "INSERT INTO game_dict (game_id, name) VALUES (?1, ?2)
-                 ON CONFLICT(game_id) DO UPDATE SET name = ?2",
+                 ON CONFLICT(game_id) DO UPDATE SET name = ?2 WHERE is_user_set = 0",
                 params![game_id, game_name],
             )?;
 
-            for session in sessions {
-                let date = session.started_date();
-
-                println!(
-                    "Inserting playtime: game_id={}, date={}, duration={}",
-                    session.game_id,
-                    date.format("%Y-%m-%dT%H:%M:%S"),
-                    session.duration
-                );
-
-                tx.execute(
-                    r#"
-                    INSERT INTO play_time(date_time, duration, game_id, migrated)
-                    VALUES (?1, ?2, ?3, ?4)
-                    "#,
-                    params![
-                        date.format("%Y-%m-%dT%H:%M:%S").to_string(),
-                        session.duration,
-                        session.game_id,
-                        source
-                    ],
-                )?;
+            tx.execute(
+                r#"
+                INSERT INTO play_time (game_id, date_time, duration, migrated)
+                VALUES (?1, ?2, ?3, ?4)
+                "#,
+                params![
+                    game_id,
+                    now.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                    time_seconds,
+                    source,
+                ],
+            )?;
 
-                tx.execute(
-                    r#"
-                    INSERT INTO overall_time (game_id, duration)
-                    VALUES (?1, ?2)
-                    ON CONFLICT(game_id) DO UPDATE SET duration = duration + ?2
-                    "#,
-                    params![session.game_id, session.duration],
-                )?;
-            }
+            add_to_overall_time(tx, game_id, time_seconds)?;
 
             Ok(())
         })
     }
 
-    pub fn apply_manual_time_correction(
+    /// Record a single session with a known duration instead of start and
+    /// end timestamps, bumping `overall_time`
+    pub fn add_duration(
         &self,
         game_id: &str,
         game_name: &str,
-        time_seconds: i64,
+        duration_secs: i64,
+        date: NaiveDateTime,
         source: &str,
     ) -> Result<()> {
-        let now = Local::now().naive_local();
+        if duration_secs <= 0 {
+            return Err(Error::InvalidInput("Duration must be positive".into()));
+        }
 
         self.db.transaction(|tx| {
             tx.execute(
                 "INSERT INTO game_dict (game_id, name) VALUES (?1, ?2)
-                 ON CONFLICT(game_id) DO UPDATE SET name = ?2",
+                 ON CONFLICT(game_id) DO UPDATE SET name = ?2 WHERE is_user_set = 0",
                 params![game_id, game_name],
             )?;
 
             tx.execute(
                 r#"
-                INSERT INTO play_time (game_id, date, time, migrated)
+                INSERT INTO play_time (game_id, date_time, duration, migrated)
                 VALUES (?1, ?2, ?3, ?4)
                 "#,
                 params![
                     game_id,
-                    now.format("%Y-%m-%dT%H:%M:%S").to_string(),
-                    time_seconds,
+                    date.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                    duration_secs,
                     source,
                 ],
             )?;
 
+            add_to_overall_time(tx, game_id, duration_secs)?;
+
             Ok(())
         })
     }
 
+    /// Insert restored sessions in one transaction, skipping any with the
+    /// same game, date and duration as an existing row
+    ///
+    /// Game names and `overall_time` are updated like `add_duration`.
+    /// Returns the number of sessions inserted.
+    pub fn import_sessions(&self, sessions: &[ImportSession]) -> Result<usize> {
+        if sessions.iter().any(|session| session.duration <= 0) {
+            return Err(Error::InvalidInput("Duration must be positive".into()));
+        }
+
+        self.db.transaction(|tx| {
+            let mut inserted = 0;
+
+            for session in sessions {
+                let date = session.date_time.format("%Y-%m-%dT%H:%M:%S").to_string();
+
+                tx.execute(
+                    "INSERT INTO game_dict (game_id, name) VALUES (?1, ?2)
+                     ON CONFLICT(game_id) DO UPDATE SET name = ?2 WHERE is_user_set = 0",
+                    params![session.game_id, session.game_name],
+                )?;
+
+                let exists: bool = tx.query_row(
+                    r#"
+                    SELECT EXISTS (
+                        SELECT 1 FROM play_time
+                        WHERE game_id = ?1 AND date_time = ?2 AND duration = ?3
+                    )
+                    "#,
+                    params![session.game_id, date, session.duration],
+                    |row| row.get(0),
+                )?;
+
+                if exists {
+                    continue;
+                }
+
+                tx.execute(
+                    r#"
+                    INSERT INTO play_time (game_id, date_time, duration, migrated)
+                    VALUES (?1, ?2, ?3, ?4)
+                    "#,
+                    params![session.game_id, date, session.duration, session.source],
+                )?;
+
+                add_to_overall_time(tx, &session.game_id, session.duration)?;
+
+                inserted += 1;
+            }
+
+            Ok(inserted)
+        })
+    }
+
+    /// Get every session of a game, newest first
+    ///
+    /// Rows stamped with the same second, such as rapid manual corrections,
+    /// keep their insertion order through the autoincrement id.
     pub fn get_game_sessions(&self, game_id: &str) -> Result<Vec<PlaySession>> {
         self.db.with_connection(|conn| {
             let mut stmt = conn.prepare(
                 r#"
-                SELECT game_id, date, time, checksum
+                SELECT
+                    game_id,
+                    date_time as session_date,
+                    duration as session_time,
+                    checksum
                 FROM play_time
                 WHERE game_id = ?1
-                ORDER BY date DESC
+                ORDER BY session_date DESC, id DESC
                 "#,
             )?;
 
             let sessions = stmt
-                .query_map(params![game_id], |row| {
-                    let date_str: String = row.get(1)?;
-                    let date = NaiveDateTime::parse_from_str(&date_str, "%Y-%m-%dT%H:%M:%S")
-                        .unwrap_or_else(|_| Local::now().naive_local());
-
-                    let started_at = date.and_local_timezone(Local).unwrap().timestamp() as f64;
-                    let duration: i64 = row.get(2)?;
-                    let duration_f64 = duration as f64;
-
-                    Ok(PlaySession {
-                        game_id: row.get(0)?,
-                        started_at,
-                        ended_at: started_at + duration_f64,
-                        duration: duration_f64,
-                        checksum: row.get(3)?,
-                    })
-                })?
+                .query_map(params![game_id], play_session_from_row)?
                 .collect::<std::result::Result<Vec<_>, _>>()?;
 
             Ok(sessions)
         })
     }
 
-    pub fn get_total_playtime(&self, game_id: &str) -> Result<i64> {
+    /// Get a game's sessions dated from `start` to `end` inclusive, newest
+    /// first
+    pub fn get_game_sessions_between(
+        &self,
+        game_id: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<PlaySession>> {
         self.db.with_connection(|conn| {
-            let total: i64 = conn.query_row(
-                "SELECT COALESCE(SUM(time), 0) FROM play_time WHERE game_id = ?1",
-                params![game_id],
-                |row| row.get(0),
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT
+                    game_id,
+                    date_time as session_date,
+                    duration as session_time,
+                    checksum
+                FROM play_time
+                WHERE game_id = ?1
+                  AND DATE(date_time) BETWEEN ?2 AND ?3
+                ORDER BY session_date DESC, id DESC
+                "#,
             )?;
 
-            Ok(total)
+            let sessions = stmt
+                .query_map(
+                    params![game_id, start.to_string(), end.to_string()],
+                    play_session_from_row,
+                )?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(sessions)
         })
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::env;
+    /// Get the session fragments that were created by splitting a recording
+    /// at midnight, newest first
+    pub fn get_synthetic_sessions(&self, game_id: &str) -> Result<Vec<PlaySession>> {
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT
+                    game_id,
+                    date_time as session_date,
+                    duration as session_time,
+                    checksum
+                FROM play_time
+                WHERE game_id = ?1 AND split_from IS NOT NULL
+                ORDER BY session_date DESC, id DESC
+                "#,
+            )?;
 
-    use super::*;
+            let sessions = stmt
+                .query_map(params![game_id], play_session_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
 
-    fn setup_test_db() -> Arc<Database> {
-        let temp_dir = env::temp_dir();
-        let db_path = temp_dir.join(format!("test_time_{}.db", uuid::Uuid::new_v4()));
-        let db = Arc::new(Database::new(&db_path).unwrap());
+            Ok(sessions)
+        })
+    }
 
-        db.with_connection(|conn| {
-            conn.execute_batch(
-                r#"
-                CREATE TABLE IF NOT EXISTS game_dict (
-                    game_id TEXT PRIMARY KEY,
-                    name TEXT NOT NULL
-                );
+    /// Get the newest `limit` sessions across all games with their names
+    pub fn get_recent_sessions_with_names(&self, limit: i64) -> Result<Vec<(Game, PlaySession)>> {
+        if limit <= 0 {
+            return Err(Error::InvalidInput("Limit must be positive".into()));
+        }
 
-                CREATE TABLE IF NOT EXISTS play_time (
-                    id INTEGER PRIMARY KEY AUTOINCREMENT,
-                    game_id TEXT NOT NULL,
-                    date TEXT NOT NULL,
-                    time INTEGER NOT NULL,
-                    checksum TEXT,
-                    migrated TEXT,
-                    FOREIGN KEY (game_id) REFERENCES game_dict(game_id)
-                );
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT
+                    pt.game_id,
+                    pt.date_time as session_date,
+                    pt.duration as session_time,
+                    pt.checksum,
+                    g.name
+                FROM play_time pt
+                JOIN game_dict g ON pt.game_id = g.game_id
+                ORDER BY session_date DESC, pt.id DESC
+                LIMIT ?1
                 "#,
             )?;
-            Ok(())
+
+            let sessions = stmt
+                .query_map(params![limit], |row| {
+                    let session = play_session_from_row(row)?;
+                    let game = Game::new(session.game_id.clone(), row.get::<_, String>(4)?);
+                    Ok((game, session))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(sessions)
         })
-        .unwrap();
+    }
 
-        db
+    /// Get one page of a game's sessions, newest first, optionally skipping
+    /// sessions shorter than `min_duration` seconds
+    pub fn get_game_sessions_paged(
+        &self,
+        game_id: &str,
+        offset: i64,
+        limit: i64,
+        min_duration: Option<i64>,
+    ) -> Result<Vec<PlaySession>> {
+        if offset < 0 || limit <= 0 {
+            return Err(Error::InvalidInput(
+                "Offset must be non-negative and limit positive".into(),
+            ));
+        }
+
+        self.db.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT
+                    game_id,
+                    date_time as session_date,
+                    duration as session_time,
+                    checksum
+                FROM play_time
+                WHERE game_id = ?1
+                  AND (?2 IS NULL OR duration >= ?2)
+                ORDER BY session_date DESC, id DESC
+                LIMIT ?3 OFFSET ?4
+                "#,
+            )?;
+
+            let sessions = stmt
+                .query_map(
+                    params![game_id, min_duration, limit, offset],
+                    play_session_from_row,
+                )?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(sessions)
+        })
     }
 
-    #[test]
-    fn test_add_time() {
-        let db = setup_test_db();
-        let dao = TimeTrackingDao::new(db);
+    /// Count a game's sessions matching the same filter as
+    /// `get_game_sessions_paged`
+    pub fn count_game_sessions(&self, game_id: &str, min_duration: Option<i64>) -> Result<i64> {
+        self.db.with_connection(|conn| {
+            let count = conn.query_row(
+                r#"
+                SELECT COUNT(*)
+                FROM play_time
+                WHERE game_id = ?1
+                  AND (?2 IS NULL OR duration >= ?2)
+                "#,
+                params![game_id, min_duration],
+                |row| row.get(0),
+            )?;
 
+            Ok(count)
+        })
+    }
+
+    pub fn get_total_playtime(&self, game_id: &str) -> Result<i64> {
+        self.db.with_connection(|conn| {
+            let total: i64 = conn.query_row(
+                "SELECT CAST(COALESCE(SUM(duration), 0) AS INTEGER) FROM play_time WHERE game_id = ?1",
+                params![game_id],
+                |row| row.get(0),
+            )?;
+
+            Ok(total)
+        })
+    }
+
+    /// Remove exact-duplicate `(game_id, date, time)` session rows, keeping
+    /// the oldest, and rebuild `overall_time` from what remains
+    ///
+    /// Totals of games without sessions are kept. Returns the number of rows removed.
+    pub fn deduplicate_sessions(&self, game_id: Option<&str>) -> Result<usize> {
+        self.db.transaction(|tx| {
+            let removed = tx.execute(
+                r#"
+                DELETE FROM play_time
+                WHERE (?1 IS NULL OR game_id = ?1)
+                  AND id NOT IN (
+                    SELECT MIN(id)
+                    FROM play_time
+                    WHERE ?1 IS NULL OR game_id = ?1
+                    GROUP BY game_id, date_time, duration
+                  )
+                "#,
+                params![game_id],
+            )?;
+
+            rebuild_overall_time(tx, game_id)?;
+
+            Ok(removed)
+        })
+    }
+
+    /// Delete a game's oldest sessions so at most `max_sessions` remain
+    ///
+    /// The removed time is folded into a single row marked "capped", dated
+    /// to the oldest removed session, so the game's total is unchanged. That
+    /// row does not count towards the cap. Returns the number of rows
+    /// removed.
+    pub fn enforce_session_cap(&self, game_id: &str, max_sessions: usize) -> Result<usize> {
+        let max_sessions = i64::try_from(max_sessions).unwrap_or(i64::MAX);
+
+        self.db.transaction(|tx| {
+            let (trimmed, first_date, duration, raw_duration): (i64, Option<String>, i64, i64) = tx
+                .query_row(
+                    r#"
+                    SELECT
+                        COUNT(*),
+                        MIN(date_time),
+                        CAST(COALESCE(SUM(duration), 0) AS INTEGER),
+                        CAST(COALESCE(SUM(COALESCE(raw_duration, duration)), 0) AS INTEGER)
+                    FROM play_time
+                    WHERE game_id = ?1
+                      AND migrated IS NOT 'capped'
+                      AND id NOT IN (
+                        SELECT id
+                        FROM play_time
+                        WHERE game_id = ?1 AND migrated IS NOT 'capped'
+                        ORDER BY date_time DESC, id DESC
+                        LIMIT ?2
+                      )
+                    "#,
+                    params![game_id, max_sessions],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+                )?;
+
+            if trimmed == 0 {
+                return Ok(0);
+            }
+
+            let removed = tx.execute(
+                r#"
+                DELETE FROM play_time
+                WHERE game_id = ?1
+                  AND migrated IS NOT 'capped'
+                  AND id NOT IN (
+                    SELECT id
+                    FROM play_time
+                    WHERE game_id = ?1 AND migrated IS NOT 'capped'
+                    ORDER BY date_time DESC, id DESC
+                    LIMIT ?2
+                  )
+                "#,
+                params![game_id, max_sessions],
+            )?;
+
+            let merged = tx.execute(
+                r#"
+                UPDATE play_time
+                SET duration = duration + ?2,
+                    raw_duration = COALESCE(raw_duration, duration) + ?3
+                WHERE game_id = ?1 AND migrated = 'capped'
+                "#,
+                params![game_id, duration, raw_duration],
+            )?;
+
+            if merged == 0 {
+                tx.execute(
+                    r#"
+                    INSERT INTO play_time (game_id, date_time, duration, raw_duration, migrated)
+                    VALUES (?1, ?2, ?3, ?4, 'capped')
+                    "#,
+                    params![game_id, first_date, duration, raw_duration],
+                )?;
+            }
+
+            Ok(removed)
+        })
+    }
+
+    /// Collapse each day before `cutoff` that has several sessions into one
+    /// row marked "compacted" with the day's total
+    ///
+    /// The row keeps the day's earliest start, so per-day and overall totals
+    /// are unchanged. Returns the number of rows removed.
+    pub fn compact_sessions_before(&self, game_id: &str, cutoff: NaiveDate) -> Result<usize> {
+        self.db.transaction(|tx| {
+            let days = {
+                let mut stmt = tx.prepare(
+                    r#"
+                    SELECT
+                        DATE(date_time),
+                        MIN(date_time),
+                        SUM(duration),
+                        SUM(COALESCE(raw_duration, duration))
+                    FROM play_time
+                    WHERE game_id = ?1 AND DATE(date_time) < ?2
+                    GROUP BY DATE(date_time)
+                    HAVING COUNT(*) > 1
+                    "#,
+                )?;
+
+                stmt.query_map(params![game_id, cutoff.to_string()], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, f64>(2)?,
+                        row.get::<_, f64>(3)?,
+                    ))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?
+            };
+
+            let mut removed = 0;
+
+            for (day, first_start, total, raw_total) in days {
+                let deleted = tx.execute(
+                    "DELETE FROM play_time WHERE game_id = ?1 AND DATE(date_time) = ?2",
+                    params![game_id, day],
+                )?;
+                tx.execute(
+                    r#"
+                    INSERT INTO play_time (game_id, date_time, duration, raw_duration, migrated)
+                    VALUES (?1, ?2, ?3, ?4, 'compacted')
+                    "#,
+                    params![game_id, first_start, total, raw_total],
+                )?;
+
+                removed += deleted - 1;
+            }
+
+            Ok(removed)
+        })
+    }
+
+    /// Recompute one game's `overall_time` from its sessions
+    ///
+    /// Returns the corrected total.
+    pub fn rebuild_overall_time_for_game(&self, game_id: &str) -> Result<i64> {
+        self.db.transaction(|tx| {
+            let total: i64 = tx.query_row(
+                "SELECT CAST(COALESCE(SUM(duration), 0) AS INTEGER) FROM play_time WHERE game_id = ?1",
+                params![game_id],
+                |row| row.get(0),
+            )?;
+
+            tx.execute(
+                r#"
+                INSERT INTO overall_time (game_id, duration)
+                VALUES (?1, ?2)
+                ON CONFLICT(game_id) DO UPDATE SET duration = ?2
+                "#,
+                params![game_id, total],
+            )?;
+
+            Ok(total)
+        })
+    }
+
+    /// Recompute `overall_time` for every game that has sessions
+    ///
+    /// Totals of games without any `play_time` rows are left alone. Returns
+    /// the number of totals written.
+    pub fn rebuild_overall_time(&self) -> Result<usize> {
+        self.db.transaction(|tx| rebuild_overall_time(tx, None))
+    }
+
+    /// Create one session per game that has an `overall_time` total but no
+    /// `play_time` rows, dated to the start of `day` and marked
+    /// "synthesized"
+    ///
+    /// Returns the number of sessions created.
+    pub fn synthesize_sessions_from_overall(&self, day: NaiveDate) -> Result<usize> {
+        let date = day.and_time(NaiveTime::MIN);
+
+        self.db.transaction(|tx| {
+            let created = tx.execute(
+                r#"
+                INSERT INTO play_time (game_id, date_time, duration, migrated)
+                SELECT o.game_id, ?1, o.duration, 'synthesized'
+                FROM overall_time o
+                WHERE o.duration > 0
+                  AND NOT EXISTS (
+                    SELECT 1 FROM play_time pt WHERE pt.game_id = o.game_id
+                  )
+                ORDER BY o.game_id
+                "#,
+                params![date.format("%Y-%m-%dT%H:%M:%S").to_string()],
+            )?;
+
+            Ok(created)
+        })
+    }
+}
+
+/// Store prepared session rows and bump `overall_time` inside `tx`
+fn record_sessions(
+    tx: &rusqlite::Transaction,
+    game_id: &str,
+    game_name: &str,
+    rows: Vec<(NaiveDateTime, PlaySession)>,
+    active_ratio: f64,
+    source: Option<&str>,
+    checksum: Option<&str>,
+) -> Result<Vec<PlaySession>> {
+    upsert_game_name(tx, game_id, game_name)?;
+
+    let recorded = insert_session_rows(tx, rows, active_ratio, source, checksum)?;
+
+    for session in &recorded {
+        add_to_overall_time(tx, &session.game_id, session.duration as i64)?;
+    }
+
+    Ok(recorded)
+}
+
+/// Insert or rename a game unless the user chose its name
+fn upsert_game_name(tx: &rusqlite::Transaction, game_id: &str, game_name: &str) -> Result<()> {
+    tx.execute(
+        "INSERT INTO game_dict (game_id, name) VALUES (?1, ?2)
+         ON CONFLICT(game_id) DO UPDATE SET name = ?2 WHERE is_user_set = 0",
+        params![game_id, game_name],
+    )?;
+
+    Ok(())
+}
+
+/// Insert the `play_time` rows of one session, scaling each by `active_ratio`
+///
+/// Rows store whole seconds that add up to the rounded session total. The
+/// first row carries `checksum`; if another session of the game already has
+/// it, nothing is inserted and no sessions are returned.
+fn insert_session_rows(
+    tx: &rusqlite::Transaction,
+    rows: Vec<(NaiveDateTime, PlaySession)>,
+    active_ratio: f64,
+    source: Option<&str>,
+    checksum: Option<&str>,
+) -> Result<Vec<PlaySession>> {
+    let split_from = (rows.len() > 1).then(|| rows[0].0.format("%Y-%m-%dT%H:%M:%S").to_string());
+    let raw_durations = whole_seconds(rows.iter().map(|(_, session)| session.duration));
+    let adjusted_durations = whole_seconds(
+        rows.iter()
+            .map(|(_, session)| session.duration * active_ratio),
+    );
+
+    let mut recorded = Vec::with_capacity(rows.len());
+
+    for (index, (date, session)) in rows.into_iter().enumerate() {
+        let checksum = checksum.filter(|_| index == 0);
+
+        log::debug!(
+            target: "playtime::time_tracking",
+            "Inserting playtime: game_id={}, date={}, duration={}",
+            session.game_id,
+            date.format("%Y-%m-%dT%H:%M:%S"),
+            session.duration
+        );
+
+        let raw_duration = raw_durations[index];
+        let adjusted_duration = adjusted_durations[index];
+
+        // A checksum the game already has skips the session; any other
+        // constraint failure is an error
+        let insert = if checksum.is_some() {
+            "INSERT OR IGNORE"
+        } else {
+            "INSERT"
+        };
+
+        let inserted = tx.execute(
+            &format!(
+                r#"
+                {} INTO play_time
+                    (date_time, duration, raw_duration, game_id, migrated, split_from, checksum)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                "#,
+                insert
+            ),
+            params![
+                date.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                adjusted_duration,
+                raw_duration,
+                session.game_id,
+                source,
+                split_from,
+                checksum
+            ],
+        )?;
+
+        if inserted == 0 {
+            return Ok(Vec::new());
+        }
+
+        recorded.push(PlaySession {
+            duration: adjusted_duration as f64,
+            checksum: checksum.map(str::to_string),
+            ..session
+        });
+    }
+
+    Ok(recorded)
+}
+
+/// Round fractional row durations to whole seconds without drifting: each
+/// row gets the difference of the rounded running totals, so the rows add up
+/// to the rounded sum
+fn whole_seconds(durations: impl Iterator<Item = f64>) -> Vec<i64> {
+    let mut total = 0.0;
+    let mut stored = 0;
+
+    durations
+        .map(|duration| {
+            total += duration;
+            let row = total.round() as i64 - stored;
+            stored += row;
+            row
+        })
+        .collect()
+}
+
+/// Split a session at midnight in `tz` unless `attribution` keeps a single
+/// crossing whole, pairing each row with the date it is recorded under
+///
+/// A crossing kept whole is dated at the time it ended, a real moment of
+/// the end day, rather than at an invented midnight start.
+fn day_rows_in<Tz: TimeZone>(
+    tz: &Tz,
+    attribution: DayAttribution,
+    session: PlaySession,
+) -> Vec<(NaiveDateTime, PlaySession)> {
+    let days_spanned =
+        (session.ended_date_in(tz).date() - session.started_date_in(tz).date()).num_days();
+
+    let sessions = match (attribution, days_spanned) {
+        (_, 0) | (DayAttribution::EndDay, 1) => vec![session],
+        _ => split_session_by_day_in(&session, tz),
+    };
+
+    sessions
+        .into_iter()
+        .map(|session| {
+            // Only an unsplit session can still cross midnight here
+            let date = if session.is_multi_day_in(tz) {
+                session.ended_date_in(tz)
+            } else {
+                session.started_date_in(tz)
+            };
+
+            (date, session)
+        })
+        .collect()
+}
+
+/// Add `duration` seconds to a game's `overall_time`, refusing to let the
+/// total overflow
+fn add_to_overall_time(tx: &rusqlite::Transaction, game_id: &str, duration: i64) -> Result<()> {
+    let current: i64 = tx
+        .query_row(
+            "SELECT CAST(duration AS INTEGER) FROM overall_time WHERE game_id = ?1",
+            params![game_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .unwrap_or(0);
+
+    if current.checked_add(duration).is_none() {
+        return Err(Error::Internal(format!(
+            "Total playtime for game {} would overflow",
+            game_id
+        )));
+    }
+
+    tx.execute(
+        r#"
+        INSERT INTO overall_time (game_id, duration)
+        VALUES (?1, ?2)
+        ON CONFLICT(game_id) DO UPDATE SET duration = duration + ?2
+        "#,
+        params![game_id, duration],
+    )?;
+
+    Ok(())
+}
+
+/// Set every game's `overall_time` to the sum of its sessions, or only
+/// `game_id`'s when given
+fn rebuild_overall_time(tx: &rusqlite::Transaction, game_id: Option<&str>) -> Result<usize> {
+    let written = tx.execute(
+        r#"
+        INSERT INTO overall_time (game_id, duration)
+        SELECT game_id, CAST(SUM(duration) AS INTEGER)
+        FROM play_time
+        WHERE ?1 IS NULL OR game_id = ?1
+        GROUP BY game_id
+        ON CONFLICT(game_id) DO UPDATE SET duration = excluded.duration
+        "#,
+        params![game_id],
+    )?;
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use chrono::{Datelike, TimeZone};
+    use rusqlite::params;
+
+    use super::*;
+    use crate::db::migrations::run_migrations;
+    use crate::db::{GamesDao, StatisticsDao};
+
+    fn setup_test_db() -> Arc<Database> {
+        let temp_dir = env::temp_dir();
+        let db_path = temp_dir.join(format!("test_time_{}.db", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::new(&db_path).unwrap());
+
+        db.with_connection(run_migrations).unwrap();
+
+        db
+    }
+
+    #[test]
+    fn test_add_time() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(db);
+
+        let noon = Local
+            .with_ymd_and_hms(2024, 3, 1, 12, 0, 0)
+            .unwrap()
+            .timestamp() as f64;
+        assert_eq!(
+            dao.add_time("123", "Test Game", noon, noon + 3600.0, None)
+                .unwrap(),
+            1
+        );
+
+        // 22:00 to 02:00 is logged across two days
+        let late = noon + 10.0 * 3600.0;
+        assert_eq!(
+            dao.add_time("123", "Test Game", late, late + 4.0 * 3600.0, None)
+                .unwrap(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_add_time_with_checksum_is_idempotent() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(Arc::clone(&db));
+        let noon = Local
+            .with_ymd_and_hms(2024, 3, 1, 12, 0, 0)
+            .unwrap()
+            .timestamp() as f64;
+
+        for expected in [1, 0] {
+            let recorded = dao
+                .add_time_with_checksum("123", "Test Game", noon, noon + 600.0, None, "abc")
+                .unwrap();
+            assert_eq!(recorded.len(), expected);
+        }
+
+        let sessions = dao.get_game_sessions("123").unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].checksum.as_deref(), Some("abc"));
+        assert_eq!(dao.get_total_playtime("123").unwrap(), 600);
+
+        // The same checksum on another game, or a split session, still lands
+        let late = noon + 10.0 * 3600.0;
+        let recorded = dao
+            .add_time_with_checksum("456", "Other", late, late + 4.0 * 3600.0, None, "abc")
+            .unwrap();
+        assert_eq!(recorded.len(), 2);
+        assert_eq!(dao.get_game_sessions("456").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_read_legacy_rows() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(Arc::clone(&db));
+
+        db.with_connection(|conn| {
+            conn.execute(
+                "INSERT INTO game_dict (game_id, name) VALUES ('123', 'Test Game')",
+                [],
+            )?;
+            conn.execute(
+                "INSERT INTO play_time (date_time, duration, game_id)
+                 VALUES ('2024-01-01T10:00:00', 1800, '123')",
+                [],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        let noon = Local
+            .with_ymd_and_hms(2024, 3, 1, 12, 0, 0)
+            .unwrap()
+            .timestamp() as f64;
+        dao.add_time("123", "Test Game", noon, noon + 600.0, None)
+            .unwrap();
+
+        let sessions = dao.get_game_sessions("123").unwrap();
+        assert_eq!(sessions.len(), 2);
+
+        let legacy = &sessions[1];
+        assert_eq!(legacy.duration, 1800.0);
+        assert_eq!(
+            legacy
+                .started_date()
+                .format("%Y-%m-%dT%H:%M:%S")
+                .to_string(),
+            "2024-01-01T10:00:00"
+        );
+
+        assert_eq!(dao.get_total_playtime("123").unwrap(), 2400);
+    }
+
+    #[test]
+    fn test_deduplicate_sessions() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(Arc::clone(&db));
+
+        db.with_connection(|conn| {
+            conn.execute_batch(
+                r#"
+                INSERT INTO game_dict (game_id, name) VALUES ('123', 'A'), ('456', 'B');
+                INSERT INTO play_time (game_id, date_time, duration) VALUES
+                    ('123', '2024-01-01T10:00:00', 600),
+                    ('123', '2024-01-01T10:00:00', 600),
+                    ('123', '2024-01-01T10:00:00', 600),
+                    ('123', '2024-01-01T10:00:00', 300),
+                    ('456', '2024-01-01T10:00:00', 600);
+                INSERT INTO play_time (game_id, date_time, duration)
+                    VALUES ('456', '2024-01-01T10:00:00', 600);
+                INSERT INTO overall_time (game_id, duration) VALUES ('123', 2100), ('456', 1200);
+                "#,
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(dao.deduplicate_sessions(Some("123")).unwrap(), 2);
+        assert_eq!(dao.get_total_playtime("123").unwrap(), 900);
+        assert_eq!(dao.get_total_playtime("456").unwrap(), 1200);
+
+        assert_eq!(dao.deduplicate_sessions(None).unwrap(), 1);
+        assert_eq!(dao.get_total_playtime("456").unwrap(), 600);
+
+        let overall: Vec<(String, i64)> = db
+            .with_connection(|conn| {
+                let mut stmt =
+                    conn.prepare("SELECT game_id, duration FROM overall_time ORDER BY game_id")?;
+                let rows = stmt
+                    .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(rows)
+            })
+            .unwrap();
+        assert_eq!(overall, vec![("123".into(), 900), ("456".into(), 600)]);
+    }
+
+    #[test]
+    fn test_deduplicate_sessions_keeps_totals_without_sessions() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(Arc::clone(&db));
+
+        db.with_connection(|conn| {
+            conn.execute_batch(
+                r#"
+                INSERT INTO game_dict (game_id, name) VALUES ('123', 'A'), ('456', 'B');
+                INSERT INTO play_time (game_id, date_time, duration) VALUES
+                    ('123', '2024-01-01T10:00:00', 600),
+                    ('123', '2024-01-01T10:00:00', 600);
+                INSERT INTO overall_time (game_id, duration) VALUES ('123', 1200), ('456', 5400);
+                "#,
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(dao.deduplicate_sessions(None).unwrap(), 1);
+        assert_eq!(dao.deduplicate_sessions(Some("456")).unwrap(), 0);
+
+        let overall: Vec<(String, i64)> = db
+            .with_connection(|conn| {
+                let mut stmt =
+                    conn.prepare("SELECT game_id, duration FROM overall_time ORDER BY game_id")?;
+                let rows = stmt
+                    .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(rows)
+            })
+            .unwrap();
+        assert_eq!(overall, vec![("123".into(), 600), ("456".into(), 5400)]);
+    }
+
+    #[test]
+    fn test_get_synthetic_sessions() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(db);
+
+        let noon = Local
+            .with_ymd_and_hms(2024, 3, 1, 12, 0, 0)
+            .unwrap()
+            .timestamp() as f64;
+        let late = Local
+            .with_ymd_and_hms(2024, 3, 1, 22, 0, 0)
+            .unwrap()
+            .timestamp() as f64;
+
+        dao.add_time("123", "Test Game", noon, noon + 3600.0, None)
+            .unwrap();
+        dao.add_time("123", "Test Game", late, late + 4.0 * 3600.0, None)
+            .unwrap();
+
+        assert_eq!(dao.get_game_sessions("123").unwrap().len(), 3);
+
+        let synthetic = dao.get_synthetic_sessions("123").unwrap();
+        assert_eq!(synthetic.len(), 2);
+        assert!(synthetic.iter().all(|session| session.started_at != noon));
+    }
+
+    #[test]
+    fn test_overall_time_overflow_is_rejected() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(Arc::clone(&db));
         let now = Local::now().timestamp() as f64;
-        let result = dao.add_time("123", "Test Game", now, now + 3600.0, None);
 
-        assert!(result.is_ok());
+        assert!(matches!(
+            dao.add_time("123", "Test Game", now, 1e20, None),
+            Err(Error::InvalidInput(_))
+        ));
+
+        db.with_connection(|conn| {
+            conn.execute_batch(&format!(
+                "INSERT INTO game_dict (game_id, name) VALUES ('123', 'Test Game');
+                 INSERT INTO overall_time (game_id, duration) VALUES ('123', {});",
+                i64::MAX - 10
+            ))?;
+            Ok(())
+        })
+        .unwrap();
+
+        assert!(matches!(
+            dao.add_time("123", "Test Game", now, now + 3600.0, None),
+            Err(Error::Internal(_))
+        ));
+
+        let overall: i64 = db
+            .with_connection(|conn| {
+                Ok(conn.query_row(
+                    "SELECT duration FROM overall_time WHERE game_id = '123'",
+                    [],
+                    |row| row.get(0),
+                )?)
+            })
+            .unwrap();
+        assert_eq!(overall, i64::MAX - 10);
+        assert!(dao.get_game_sessions("123").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_get_recent_sessions_with_names() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(db);
+        let at = |day: u32| {
+            Local
+                .with_ymd_and_hms(2024, 3, day, 12, 0, 0)
+                .unwrap()
+                .timestamp() as f64
+        };
+
+        dao.add_time("123", "Alpha", at(1), at(1) + 600.0, None)
+            .unwrap();
+        dao.add_time("456", "Beta", at(3), at(3) + 600.0, None)
+            .unwrap();
+        dao.add_time("123", "Alpha", at(2), at(2) + 600.0, None)
+            .unwrap();
+
+        let recent = dao.get_recent_sessions_with_names(2).unwrap();
+        let summary: Vec<_> = recent
+            .iter()
+            .map(|(game, session)| (game.name.as_str(), session.started_at))
+            .collect();
+
+        assert_eq!(summary, vec![("Beta", at(3)), ("Alpha", at(2))]);
+        assert!(dao.get_recent_sessions_with_names(0).is_err());
+    }
+
+    #[test]
+    fn test_enforce_session_cap() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(db);
+
+        for day in 1..=5 {
+            let date = NaiveDate::from_ymd_opt(2024, 3, day)
+                .and_then(|d| d.and_hms_opt(12, 0, 0))
+                .unwrap();
+            dao.add_duration("123", "Test Game", 600, date, "manual")
+                .unwrap();
+        }
+        dao.add_duration(
+            "456",
+            "Other Game",
+            300,
+            NaiveDate::from_ymd_opt(2024, 3, 1)
+                .and_then(|d| d.and_hms_opt(12, 0, 0))
+                .unwrap(),
+            "manual",
+        )
+        .unwrap();
+
+        assert_eq!(dao.enforce_session_cap("123", 2).unwrap(), 3);
+        assert_eq!(dao.enforce_session_cap("123", 2).unwrap(), 0);
+
+        // The newest two remain, plus the "capped" row dated to day 1
+        let sessions: Vec<(u32, f64)> = dao
+            .get_game_sessions("123")
+            .unwrap()
+            .iter()
+            .map(|session| (session.started_date().day(), session.duration))
+            .collect();
+        assert_eq!(sessions, vec![(5, 600.0), (4, 600.0), (1, 1800.0)]);
+        assert_eq!(dao.get_game_sessions("456").unwrap().len(), 1);
+        assert_eq!(dao.get_total_playtime("123").unwrap(), 3000);
+
+        // Later trims fold into the same row
+        assert_eq!(dao.enforce_session_cap("123", 1).unwrap(), 1);
+        assert_eq!(dao.get_game_sessions("123").unwrap().len(), 2);
+        assert_eq!(dao.get_total_playtime("123").unwrap(), 3000);
+
+        let overall: i64 = dao
+            .db
+            .with_connection(|conn| {
+                Ok(conn.query_row(
+                    "SELECT duration FROM overall_time WHERE game_id = '123'",
+                    [],
+                    |row| row.get(0),
+                )?)
+            })
+            .unwrap();
+        assert_eq!(overall, 3000);
+    }
+
+    #[test]
+    fn test_apply_manual_time_correction() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(Arc::clone(&db));
+
+        dao.apply_manual_time_correction("123", "Test Game", 3600, "manual")
+            .unwrap();
+        dao.apply_manual_time_correction("123", "Test Game", -600, "manual")
+            .unwrap();
+
+        assert_eq!(dao.get_total_playtime("123").unwrap(), 3000);
+
+        let overall: i64 = db
+            .with_connection(|conn| {
+                Ok(conn.query_row(
+                    "SELECT duration FROM overall_time WHERE game_id = '123'",
+                    [],
+                    |row| row.get(0),
+                )?)
+            })
+            .unwrap();
+        assert_eq!(overall, 3000);
+    }
+
+    #[test]
+    fn test_manual_corrections_in_same_second_keep_order() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(Arc::clone(&db));
+
+        for time_seconds in [3600, -600, 120] {
+            dao.apply_manual_time_correction("123", "Test Game", time_seconds, "manual")
+                .unwrap();
+        }
+
+        // Pin every correction to the same second
+        db.with_connection(|conn| {
+            conn.execute("UPDATE play_time SET date_time = '2024-03-01T12:00:00'", [])?;
+            Ok(())
+        })
+        .unwrap();
+
+        let durations: Vec<f64> = dao
+            .get_game_sessions("123")
+            .unwrap()
+            .iter()
+            .map(|session| session.duration)
+            .collect();
+        assert_eq!(durations, vec![120.0, -600.0, 3600.0]);
+    }
+
+    #[test]
+    fn test_rebuild_overall_time_for_game() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(Arc::clone(&db));
+
+        db.with_connection(|conn| {
+            conn.execute_batch(
+                r#"
+                INSERT INTO game_dict (game_id, name) VALUES ('123', 'A'), ('456', 'B');
+                INSERT INTO play_time (game_id, date_time, duration) VALUES
+                    ('123', '2024-01-01T10:00:00', 600),
+                    ('123', '2024-01-02T10:00:00', 300),
+                    ('456', '2024-01-01T10:00:00', 600);
+                INSERT INTO overall_time (game_id, duration) VALUES ('123', 50), ('456', 70);
+                "#,
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(dao.rebuild_overall_time_for_game("123").unwrap(), 900);
+
+        let overall: Vec<(String, i64)> = db
+            .with_connection(|conn| {
+                let mut stmt =
+                    conn.prepare("SELECT game_id, duration FROM overall_time ORDER BY game_id")?;
+                let rows = stmt
+                    .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(rows)
+            })
+            .unwrap();
+        assert_eq!(overall, vec![("123".into(), 900), ("456".into(), 70)]);
+    }
+
+    #[test]
+    fn test_add_time_batch() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(Arc::clone(&db));
+        let start = Local
+            .with_ymd_and_hms(2024, 3, 1, 10, 0, 0)
+            .unwrap()
+            .timestamp() as f64;
+        let entries: Vec<TimeEntry> = (0..1000)
+            .map(|i| {
+                let started_at = start + f64::from(i) * 7200.0;
+                TimeEntry {
+                    game_id: format!("{}", i % 3),
+                    game_name: format!("Game {}", i % 3),
+                    started_at,
+                    ended_at: started_at + 1800.0,
+                    source: None,
+                }
+            })
+            .collect();
+
+        dao.add_time_batch(&entries, false).unwrap();
+
+        let rows: i64 = db
+            .with_connection(|conn| {
+                Ok(conn.query_row("SELECT COUNT(*) FROM play_time", [], |row| row.get(0))?)
+            })
+            .unwrap();
+        // Two-hour spacing lands some sessions across midnight
+        assert!(rows >= 1000);
+        assert_eq!(dao.get_total_playtime("0").unwrap(), 334 * 1800);
+        assert_eq!(dao.get_total_playtime("1").unwrap(), 333 * 1800);
+        assert_eq!(dao.get_total_playtime("2").unwrap(), 333 * 1800);
+    }
+
+    #[test]
+    fn test_add_time_batch_deferred_totals_match() {
+        let start = Local
+            .with_ymd_and_hms(2024, 3, 1, 10, 0, 0)
+            .unwrap()
+            .timestamp() as f64;
+        let entries: Vec<TimeEntry> = (0..20)
+            .map(|i| {
+                let started_at = start + f64::from(i) * 7200.0;
+                TimeEntry {
+                    game_id: format!("{}", i % 3),
+                    game_name: format!("Game {}", i % 3),
+                    started_at,
+                    ended_at: started_at + 1800.0,
+                    source: None,
+                }
+            })
+            .collect();
+
+        let overall_after_batch = |defer_totals: bool| {
+            let db = setup_test_db();
+            let dao = TimeTrackingDao::new(Arc::clone(&db));
+
+            // A game outside the batch whose total exceeds its sessions
+            db.with_connection(|conn| {
+                conn.execute_batch(
+                    r#"
+                    INSERT INTO game_dict (game_id, name) VALUES ('999', 'Other');
+                    INSERT INTO play_time (game_id, date_time, duration)
+                        VALUES ('999', '2024-01-01T10:00:00', 600);
+                    INSERT INTO overall_time (game_id, duration) VALUES ('999', 5000);
+                    "#,
+                )?;
+                Ok(())
+            })
+            .unwrap();
+
+            dao.add_time_batch(&entries, defer_totals).unwrap();
+
+            db.with_connection(|conn| {
+                let mut stmt =
+                    conn.prepare("SELECT game_id, duration FROM overall_time ORDER BY game_id")?;
+                let rows = stmt
+                    .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<std::result::Result<Vec<(String, i64)>, _>>()?;
+                Ok(rows)
+            })
+            .unwrap()
+        };
+
+        let immediate = overall_after_batch(false);
+        assert_eq!(
+            immediate,
+            vec![
+                ("0".into(), 7 * 1800),
+                ("1".into(), 7 * 1800),
+                ("2".into(), 6 * 1800),
+                ("999".into(), 5000)
+            ]
+        );
+        assert_eq!(overall_after_batch(true), immediate);
+    }
+
+    #[test]
+    fn test_add_time_batch_rejects_invalid_entries() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(Arc::clone(&db));
+        let entry = |game_id: &str, started_at: f64, ended_at: f64| TimeEntry {
+            game_id: game_id.into(),
+            game_name: format!("Game {}", game_id),
+            started_at,
+            ended_at,
+            source: None,
+        };
+
+        let err = dao
+            .add_time_batch(
+                &[
+                    entry("123", 1000.0, 1600.0),
+                    entry("456", 2000.0, 2000.0),
+                    entry("789", 3000.0, 2500.0),
+                ],
+                false,
+            )
+            .unwrap_err();
+
+        let message = err.to_string();
+        assert!(matches!(err, Error::InvalidInput(_)));
+        assert!(message.contains("#1 (456)") && message.contains("#2 (789)"));
+        assert!(dao.get_game_sessions("123").unwrap().is_empty());
+        assert_eq!(dao.get_total_playtime("123").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_add_time_rejects_non_finite() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(db);
+        let now = Local::now().timestamp() as f64;
+
+        for (started_at, ended_at) in [
+            (f64::NAN, now),
+            (now, f64::NAN),
+            (now, f64::INFINITY),
+            (f64::NEG_INFINITY, now),
+        ] {
+            let err = dao
+                .add_time("123", "Test Game", started_at, ended_at, None)
+                .unwrap_err();
+            assert!(matches!(err, Error::InvalidInput(_)));
+        }
+
+        assert!(dao.get_game_sessions("123").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_idle_split_session_stores_whole_seconds() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(Arc::clone(&db));
+        let late = Local
+            .with_ymd_and_hms(2024, 3, 1, 23, 0, 0)
+            .unwrap()
+            .timestamp() as f64
+            + 0.4;
+
+        let recorded = dao
+            .add_time_with_idle("123", "Test Game", late, late + 7200.0, 1000.0, None)
+            .unwrap();
+        assert_eq!(recorded.len(), 2);
+        let recorded_total = recorded.iter().map(|s| s.duration).sum::<f64>() as i64;
+        // Splitting at 23:59:59 drops at most a second of the 6200 active
+        assert!((6199..=6200).contains(&recorded_total));
+
+        let (fractional, duration, raw): (i64, i64, i64) = db
+            .with_connection(|conn| {
+                Ok(conn.query_row(
+                    "SELECT
+                        SUM(typeof(duration) != 'integer' OR typeof(raw_duration) != 'integer'),
+                        SUM(duration),
+                        SUM(raw_duration)
+                     FROM play_time",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                )?)
+            })
+            .unwrap();
+        assert_eq!(fractional, 0);
+        assert_eq!(duration, recorded_total);
+        assert!((7199..=7200).contains(&raw));
+        assert_eq!(dao.get_total_playtime("123").unwrap(), recorded_total);
+    }
+
+    #[test]
+    fn test_add_time_rejects_runaway_span() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(Arc::clone(&db));
+        let noon = Local
+            .with_ymd_and_hms(2024, 3, 1, 12, 0, 0)
+            .unwrap()
+            .timestamp() as f64;
+        let days = |n: f64| n * 86_400.0;
+
+        let err = dao
+            .add_time("123", "Test Game", noon - days(10_000.0), noon, None)
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidInput(_)));
+        assert!(dao.get_game_sessions("123").unwrap().is_empty());
+
+        assert_eq!(
+            dao.add_time("123", "Test Game", noon - days(3.0), noon, None)
+                .unwrap(),
+            4
+        );
+
+        let strict = TimeTrackingDao::new(db).with_max_span_days(2);
+        assert!(
+            strict
+                .add_time("456", "Test Game", noon - days(3.0), noon, None)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_add_time_keeps_user_set_name() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(Arc::clone(&db));
+        let games = GamesDao::new(Arc::clone(&db));
+        let noon = Local
+            .with_ymd_and_hms(2024, 3, 1, 12, 0, 0)
+            .unwrap()
+            .timestamp() as f64;
+
+        dao.add_time("123", "Steam Name", noon, noon + 600.0, None)
+            .unwrap();
+        dao.add_time("123", "Updated Steam Name", noon, noon + 600.0, None)
+            .unwrap();
+        assert_eq!(
+            games.get_game("123").unwrap().unwrap().name,
+            "Updated Steam Name"
+        );
+
+        games.rename_game("123", "My Name").unwrap();
+        dao.add_time("123", "Steam Name", noon, noon + 600.0, None)
+            .unwrap();
+
+        assert_eq!(games.get_game("123").unwrap().unwrap().name, "My Name");
+        assert!(
+            games
+                .rename_game("456", "Missing")
+                .unwrap_err()
+                .is_not_found()
+        );
+    }
+
+    #[test]
+    fn test_synthesize_sessions_from_overall() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(Arc::clone(&db));
+
+        db.with_connection(|conn| {
+            conn.execute_batch(
+                r#"
+                INSERT INTO game_dict (game_id, name) VALUES ('123', 'Lost'), ('456', 'Kept');
+                INSERT INTO overall_time (game_id, duration)
+                VALUES ('123', 7200), ('456', 600), ('789', 0);
+                INSERT INTO play_time (game_id, date_time, duration)
+                VALUES ('456', '2024-01-01T10:00:00', 600);
+                "#,
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        let day = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+        assert_eq!(dao.synthesize_sessions_from_overall(day).unwrap(), 1);
+        assert_eq!(dao.synthesize_sessions_from_overall(day).unwrap(), 0);
+
+        let sessions = dao.get_game_sessions("123").unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].started_date().date(), day);
+        assert_eq!(dao.get_total_playtime("123").unwrap(), 7200);
+        assert_eq!(dao.get_game_sessions("456").unwrap().len(), 1);
+
+        let source: String = db
+            .with_connection(|conn| {
+                Ok(conn.query_row(
+                    "SELECT migrated FROM play_time WHERE game_id = '123'",
+                    [],
+                    |row| row.get(0),
+                )?)
+            })
+            .unwrap();
+        assert_eq!(source, "synthesized");
+    }
+
+    #[test]
+    fn test_game_sessions_paged() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(Arc::clone(&db));
+
+        db.with_connection(|conn| {
+            for day in 1..=10 {
+                let time = if day % 2 == 0 { 60 } else { 1800 };
+                conn.execute(
+                    "INSERT INTO play_time (game_id, date_time, duration) VALUES ('123', ?1, ?2)",
+                    params![format!("2024-01-{:02}T10:00:00", day), time],
+                )?;
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        let first = dao.get_game_sessions_paged("123", 0, 4, None).unwrap();
+        let second = dao.get_game_sessions_paged("123", 4, 4, None).unwrap();
+        let last = dao.get_game_sessions_paged("123", 8, 4, None).unwrap();
+
+        assert_eq!((first.len(), second.len(), last.len()), (4, 4, 2));
+        assert!(first.last().unwrap().started_at > second[0].started_at);
+        assert!(second.last().unwrap().started_at > last[0].started_at);
+        assert_eq!(dao.count_game_sessions("123", None).unwrap(), 10);
+
+        let long = dao
+            .get_game_sessions_paged("123", 0, 10, Some(300))
+            .unwrap();
+        assert_eq!(long.len(), 5);
+        assert!(long.iter().all(|session| session.duration >= 300.0));
+        assert_eq!(dao.count_game_sessions("123", Some(300)).unwrap(), 5);
+
+        assert!(dao.get_game_sessions_paged("123", -1, 4, None).is_err());
+    }
+
+    #[test]
+    fn test_compact_sessions_keeps_raw_durations() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(Arc::clone(&db));
+        let stats = StatisticsDao::new(Arc::clone(&db));
+        let at = |hour: u32| {
+            Local
+                .with_ymd_and_hms(2024, 3, 1, hour, 0, 0)
+                .unwrap()
+                .timestamp() as f64
+        };
+
+        dao.add_time_with_idle("123", "Test Game", at(10), at(11), 600.0, None)
+            .unwrap();
+        dao.add_time_with_idle("123", "Test Game", at(14), at(15), 0.0, None)
+            .unwrap();
+        let before = stats.get_adjustment_summary("123").unwrap();
+        assert_eq!((before.adjusted_time, before.raw_time), (6600, 7200));
+
+        let cutoff = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+        assert_eq!(dao.compact_sessions_before("123", cutoff).unwrap(), 1);
+
+        let after = stats.get_adjustment_summary("123").unwrap();
+        assert_eq!(
+            (after.adjusted_time, after.raw_time),
+            (before.adjusted_time, before.raw_time)
+        );
+    }
+
+    #[test]
+    fn test_add_time_surfaces_constraint_failures() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(Arc::clone(&db));
+        let noon = Local
+            .with_ymd_and_hms(2024, 3, 1, 12, 0, 0)
+            .unwrap()
+            .timestamp() as f64;
+
+        db.with_connection(|conn| {
+            conn.execute(
+                "CREATE UNIQUE INDEX test_one_session_per_start ON play_time(game_id, date_time)",
+                [],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        dao.add_time("123", "Test Game", noon, noon + 600.0, None)
+            .unwrap();
+        assert!(
+            dao.add_time("123", "Test Game", noon, noon + 900.0, None)
+                .is_err()
+        );
+        assert_eq!(dao.get_total_playtime("123").unwrap(), 600);
+    }
+
+    #[test]
+    fn test_add_time_with_key_is_idempotent() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(db);
+        let noon = Local
+            .with_ymd_and_hms(2024, 3, 1, 12, 0, 0)
+            .unwrap()
+            .timestamp() as f64;
+
+        let (first_rows, first) = dao
+            .add_time_with_key(
+                "123",
+                "Test Game",
+                noon,
+                noon + 600.0,
+                None,
+                Some("retry-1"),
+            )
+            .unwrap();
+        let (retry_rows, retry) = dao
+            .add_time_with_key(
+                "123",
+                "Test Game",
+                noon,
+                noon + 601.0,
+                None,
+                Some("retry-1"),
+            )
+            .unwrap();
+
+        assert_eq!(first_rows, 1);
+        assert_eq!(first.len(), 1);
+        assert_eq!(retry_rows, first_rows);
+        assert!(retry.is_empty());
+        assert_eq!(dao.get_game_sessions("123").unwrap().len(), 1);
+        assert_eq!(dao.get_total_playtime("123").unwrap(), 600);
+
+        dao.add_time_with_key("123", "Test Game", noon, noon + 600.0, None, None)
+            .unwrap();
+        assert_eq!(dao.get_game_sessions("123").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_import_sessions_is_idempotent() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(Arc::clone(&db));
+        let at = |day: u32| {
+            NaiveDate::from_ymd_opt(2024, 3, day)
+                .and_then(|d| d.and_hms_opt(10, 0, 0))
+                .unwrap()
+        };
+        let import = |game_id: &str, day: u32, duration: i64| ImportSession {
+            game_id: game_id.into(),
+            game_name: format!("Game {}", game_id),
+            date_time: at(day),
+            duration,
+            source: Some("backup".into()),
+        };
+        let batch = vec![
+            import("123", 1, 600),
+            import("123", 2, 900),
+            import("456", 1, 300),
+        ];
+
+        assert_eq!(dao.import_sessions(&batch).unwrap(), 3);
+        assert_eq!(dao.import_sessions(&batch).unwrap(), 0);
+
+        assert_eq!(dao.get_game_sessions("123").unwrap().len(), 2);
+        assert_eq!(dao.get_total_playtime("123").unwrap(), 1500);
+        assert_eq!(dao.get_total_playtime("456").unwrap(), 300);
+
+        // Same day but a different duration is a distinct session
+        assert_eq!(dao.import_sessions(&[import("456", 1, 301)]).unwrap(), 1);
+        assert_eq!(dao.get_total_playtime("456").unwrap(), 601);
+
+        assert!(dao.import_sessions(&[import("789", 1, 0)]).is_err());
+    }
+
+    #[test]
+    fn test_utc_offset_moves_day_boundary() {
+        let start = NaiveDate::from_ymd_opt(2024, 3, 1)
+            .and_then(|d| d.and_hms_opt(22, 0, 0))
+            .unwrap()
+            .and_utc()
+            .timestamp() as f64;
+        let stored_rows = |offset_hours: i32| {
+            let db = setup_test_db();
+            let offset = FixedOffset::east_opt(offset_hours * 3600).unwrap();
+
+            TimeTrackingDao::new(Arc::clone(&db))
+                .with_utc_offset(offset)
+                .add_time("123", "Test Game", start, start + 4.0 * 3600.0, None)
+                .unwrap();
+
+            db.with_connection(|conn| {
+                let mut stmt =
+                    conn.prepare("SELECT date_time, duration FROM play_time ORDER BY date_time")?;
+                let rows = stmt
+                    .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<std::result::Result<Vec<(String, i64)>, _>>()?;
+                Ok(rows)
+            })
+            .unwrap()
+        };
+
+        // 22:00-02:00 in UTC, 23:00-03:00 at UTC+1, 01:00-05:00 at UTC+3
+        assert_eq!(
+            stored_rows(0),
+            vec![
+                ("2024-03-01T22:00:00".into(), 7199),
+                ("2024-03-02T00:00:00".into(), 7200)
+            ]
+        );
+        assert_eq!(
+            stored_rows(1),
+            vec![
+                ("2024-03-01T23:00:00".into(), 3599),
+                ("2024-03-02T00:00:00".into(), 10_800)
+            ]
+        );
+        assert_eq!(stored_rows(3), vec![("2024-03-02T01:00:00".into(), 14_400)]);
+    }
+
+    #[test]
+    fn test_get_game_sessions_between() {
+        let db = setup_test_db();
+        let dao = TimeTrackingDao::new(Arc::clone(&db));
+
+        db.with_connection(|conn| {
+            conn.execute_batch(
+                r#"
+                INSERT INTO game_dict (game_id, name) VALUES ('123', 'A'), ('456', 'B');
+                INSERT INTO play_time (game_id, date_time, duration) VALUES
+                    ('123', '2024-03-03T23:59:59', 100),
+                    ('123', '2024-03-04T00:00:00', 200),
+                    ('123', '2024-03-10T23:59:59', 300),
+                    ('123', '2024-03-11T00:00:00', 400),
+                    ('456', '2024-03-05T10:00:00', 500);
+                "#,
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        let monday = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let sunday = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+
+        let durations: Vec<f64> = dao
+            .get_game_sessions_between("123", monday, sunday)
+            .unwrap()
+            .iter()
+            .map(|session| session.duration)
+            .collect();
+        assert_eq!(durations, vec![300.0, 200.0]);
+
+        assert!(
+            dao.get_game_sessions_between("123", sunday, monday)
+                .unwrap()
+                .is_empty()
+        );
     }
 }
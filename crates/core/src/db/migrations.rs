@@ -1,8 +1,168 @@
+use std::collections::HashMap;
+
 use rusqlite::Connection;
 
 use crate::{Error, Result};
 
-const SCHEMA_VERSION: i32 = 8;
+/// Schema version a fully migrated database is at
+pub const SCHEMA_VERSION: i32 = 23;
+
+/// A schema migration and what it changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MigrationInfo {
+    pub version: i32,
+    pub description: &'static str,
+    /// Whether the migration is expected to delete rows
+    pub destructive: bool,
+    /// Whether [`apply_down_migration`] can undo the schema change
+    pub reversible: bool,
+}
+
+const MIGRATIONS: &[MigrationInfo] = &[
+    MigrationInfo {
+        version: 1,
+        description: "create play_time, overall_time and game_dict",
+        destructive: false,
+        reversible: false,
+    },
+    MigrationInfo {
+        version: 2,
+        description: "index play_time by date and game",
+        destructive: false,
+        reversible: false,
+    },
+    MigrationInfo {
+        version: 3,
+        description: "add play_time.migrated",
+        destructive: false,
+        reversible: false,
+    },
+    MigrationInfo {
+        version: 4,
+        description: "recreate play_time date index",
+        destructive: false,
+        reversible: false,
+    },
+    MigrationInfo {
+        version: 5,
+        description: "add game_file_checksum",
+        destructive: false,
+        reversible: false,
+    },
+    MigrationInfo {
+        version: 6,
+        description: "replace play_time indexes with date_time indexes",
+        destructive: false,
+        reversible: false,
+    },
+    MigrationInfo {
+        version: 7,
+        description: "add lookup indexes for totals, games and checksums",
+        destructive: false,
+        reversible: true,
+    },
+    MigrationInfo {
+        version: 8,
+        description: "remove checksums for unknown games",
+        destructive: true,
+        reversible: true,
+    },
+    MigrationInfo {
+        version: 9,
+        description: "add play_time.date, time and checksum",
+        destructive: false,
+        reversible: false,
+    },
+    MigrationInfo {
+        version: 10,
+        description: "add stats_snapshot",
+        destructive: false,
+        reversible: false,
+    },
+    MigrationInfo {
+        version: 11,
+        description: "add play_time.raw_duration",
+        destructive: false,
+        reversible: false,
+    },
+    MigrationInfo {
+        version: 12,
+        description: "rebuild play_time with an autoincrement id",
+        destructive: false,
+        reversible: false,
+    },
+    MigrationInfo {
+        version: 13,
+        description: "add game_total_goal",
+        destructive: false,
+        reversible: false,
+    },
+    MigrationInfo {
+        version: 14,
+        description: "add game_dict.is_user_set",
+        destructive: false,
+        reversible: false,
+    },
+    MigrationInfo {
+        version: 15,
+        description: "add game_metadata",
+        destructive: false,
+        reversible: false,
+    },
+    MigrationInfo {
+        version: 16,
+        description: "add day_note",
+        destructive: false,
+        reversible: false,
+    },
+    MigrationInfo {
+        version: 17,
+        description: "add applied_keys",
+        destructive: false,
+        reversible: false,
+    },
+    MigrationInfo {
+        version: 18,
+        description: "fold play_time.date and time into date_time and duration",
+        destructive: false,
+        reversible: false,
+    },
+    MigrationInfo {
+        version: 19,
+        description: "add play_time.split_from",
+        destructive: false,
+        reversible: true,
+    },
+    MigrationInfo {
+        version: 20,
+        description: "add settings",
+        destructive: false,
+        reversible: true,
+    },
+    MigrationInfo {
+        version: 21,
+        description: "make play_time checksums unique per game",
+        destructive: false,
+        reversible: true,
+    },
+    MigrationInfo {
+        version: 22,
+        description: "add goals",
+        destructive: false,
+        reversible: true,
+    },
+    MigrationInfo {
+        version: 23,
+        description: "add applied_keys.row_count",
+        destructive: false,
+        reversible: true,
+    },
+];
+
+/// List every schema migration in order
+pub fn list() -> Vec<MigrationInfo> {
+    MIGRATIONS.to_vec()
+}
 
 pub fn run_migrations(conn: &mut Connection) -> Result<()> {
     ensure_migration_table(conn)?;
@@ -17,15 +177,140 @@ pub fn run_migrations(conn: &mut Connection) -> Result<()> {
         )));
     }
 
-    for version in (current_version + 1)..=SCHEMA_VERSION {
+    migrate_up(conn, current_version, SCHEMA_VERSION)
+}
+
+/// Move the schema to `target_version`, applying or reverting migrations
+///
+/// Stepping down only works through reversible migrations (v7, v8 and v19
+/// to v23) and happens in a single transaction, so the schema is either fully
+/// at the target or left untouched. Rows removed by a destructive migration
+/// are not restored.
+pub fn run_migrations_to(conn: &mut Connection, target_version: i32) -> Result<()> {
+    ensure_migration_table(conn)?;
+
+    if !(0..=SCHEMA_VERSION).contains(&target_version) {
+        return Err(Error::InvalidInput(format!(
+            "Target schema version must be between 0 and {}, got {}",
+            SCHEMA_VERSION, target_version
+        )));
+    }
+
+    let current_version = get_schema_version(conn)?;
+
+    if target_version >= current_version {
+        return migrate_up(conn, current_version, target_version);
+    }
+
+    if let Some(version) =
+        ((target_version + 1)..=current_version).find(|version| !is_reversible(*version))
+    {
+        return Err(Error::InvalidInput(format!(
+            "Migration v{} is not reversible, cannot step down to v{}",
+            version, target_version
+        )));
+    }
+
+    let tx = conn.transaction()?;
+
+    for version in ((target_version + 1)..=current_version).rev() {
+        revert_migration(&tx, version)?;
+        tx.execute("DELETE FROM migration WHERE id = ?1", [version])?;
+    }
+
+    tx.commit()?;
+
+    log::info!(
+        target: "playtime::migrations",
+        "Stepped schema down from v{} to v{}",
+        current_version,
+        target_version
+    );
+
+    Ok(())
+}
+
+/// Revert the current schema version, which must be `version`
+pub fn apply_down_migration(conn: &mut Connection, version: i32) -> Result<()> {
+    let current_version = get_schema_version(conn)?;
+
+    if version != current_version {
+        return Err(Error::InvalidInput(format!(
+            "Can only revert the current schema version v{}, not v{}",
+            current_version, version
+        )));
+    }
+
+    run_migrations_to(conn, version - 1)
+}
+
+fn migrate_up(conn: &mut Connection, from_version: i32, to_version: i32) -> Result<()> {
+    for version in (from_version + 1)..=to_version {
+        let before = table_row_counts(conn)?;
+
         apply_migration(conn, version).map_err(|e| {
             Error::Internal(format!("Failed to apply migration {}: {}", version, e))
         })?;
+
+        let destructive = MIGRATIONS
+            .iter()
+            .any(|migration| migration.version == version && migration.destructive);
+
+        if !destructive {
+            let shrunk = tables_with_lost_rows(&before, &table_row_counts(conn)?);
+
+            if !shrunk.is_empty() {
+                log::warn!(
+                    target: "playtime::migrations",
+                    "Non-destructive migration v{} removed rows from {:?}",
+                    version,
+                    shrunk
+                );
+            }
+        }
     }
 
     Ok(())
 }
 
+/// Count rows in every user table, keyed by table name
+fn table_row_counts(conn: &Connection) -> Result<HashMap<String, i64>> {
+    let mut stmt = conn.prepare(
+        "SELECT name FROM sqlite_master
+         WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name != 'migration'",
+    )?;
+
+    let tables = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let mut counts = HashMap::with_capacity(tables.len());
+
+    for table in tables {
+        let count = conn.query_row(&format!("SELECT COUNT(*) FROM \"{}\"", table), [], |row| {
+            row.get(0)
+        })?;
+        counts.insert(table, count);
+    }
+
+    Ok(counts)
+}
+
+/// Tables that still exist but hold fewer rows than before, sorted by name
+fn tables_with_lost_rows(
+    before: &HashMap<String, i64>,
+    after: &HashMap<String, i64>,
+) -> Vec<String> {
+    let mut shrunk: Vec<String> = before
+        .iter()
+        .filter(|(table, count)| after.get(*table).is_some_and(|after| after < count))
+        .map(|(table, _)| table.clone())
+        .collect();
+
+    shrunk.sort();
+    shrunk
+}
+
 fn ensure_migration_table(conn: &Connection) -> Result<()> {
     conn.execute(
         "CREATE TABLE IF NOT EXISTS migration (
@@ -43,6 +328,22 @@ fn get_schema_version(conn: &Connection) -> Result<i32> {
     Ok(version)
 }
 
+/// Read the schema version without creating the migration table, returning
+/// 0 for a database that was never migrated
+pub fn current_schema_version(conn: &Connection) -> Result<i32> {
+    let has_migration_table: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'migration')",
+        [],
+        |row| row.get(0),
+    )?;
+
+    if !has_migration_table {
+        return Ok(0);
+    }
+
+    get_schema_version(conn)
+}
+
 fn set_schema_version(conn: &Connection, version: i32) -> Result<()> {
     conn.execute("INSERT INTO migration (id) VALUES (?1)", [version])?;
     Ok(())
@@ -60,6 +361,21 @@ fn apply_migration(conn: &mut Connection, version: i32) -> Result<()> {
         6 => migration_v6(&tx)?,
         7 => migration_v7(&tx)?,
         8 => migration_v8(&tx)?,
+        9 => migration_v9(&tx)?,
+        10 => migration_v10(&tx)?,
+        11 => migration_v11(&tx)?,
+        12 => migration_v12(&tx)?,
+        13 => migration_v13(&tx)?,
+        14 => migration_v14(&tx)?,
+        15 => migration_v15(&tx)?,
+        16 => migration_v16(&tx)?,
+        17 => migration_v17(&tx)?,
+        18 => migration_v18(&tx)?,
+        19 => migration_v19(&tx)?,
+        20 => migration_v20(&tx)?,
+        21 => migration_v21(&tx)?,
+        22 => migration_v22(&tx)?,
+        23 => migration_v23(&tx)?,
         _ => {
             return Err(Error::Internal(format!(
                 "Unknown migration version: {}",
@@ -205,6 +521,314 @@ fn migration_v8(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+/// Reconcile `play_time` with the columns the DAOs read and write.
+///
+/// Rows written before this migration keep their data in
+/// `date_time`/`duration`; migration v18 folds both spellings back together.
+fn migration_v9(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        ALTER TABLE play_time ADD COLUMN date TEXT;
+        ALTER TABLE play_time ADD COLUMN time INTEGER;
+        ALTER TABLE play_time ADD COLUMN checksum TEXT;
+        "#,
+    )?;
+    Ok(())
+}
+
+fn migration_v10(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE stats_snapshot(
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            taken_at TEXT NOT NULL,
+            total_time INTEGER NOT NULL,
+            game_count INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_stats_snapshot_taken_at
+            ON stats_snapshot(taken_at);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Keep the original wall-clock span next to the (possibly adjusted)
+/// recorded time.
+fn migration_v11(conn: &Connection) -> Result<()> {
+    conn.execute("ALTER TABLE play_time ADD COLUMN raw_duration INTEGER", [])?;
+    Ok(())
+}
+
+/// Give `play_time` a stable autoincrement `id`.
+///
+/// SQLite can't add a primary key in place, so the table is rebuilt and rows
+/// are copied in their original insertion order. The copy is verified before
+/// the old table is dropped.
+fn migration_v12(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE play_time_new(
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            date_time TEXT,
+            duration INT,
+            game_id TEXT,
+            migrated TEXT,
+            date TEXT,
+            time INTEGER,
+            checksum TEXT,
+            raw_duration INTEGER
+        );
+
+        INSERT INTO play_time_new
+            (date_time, duration, game_id, migrated, date, time, checksum, raw_duration)
+        SELECT date_time, duration, game_id, migrated, date, time, checksum, raw_duration
+        FROM play_time
+        ORDER BY rowid;
+        "#,
+    )?;
+
+    let original: i64 = conn.query_row("SELECT COUNT(*) FROM play_time", [], |row| row.get(0))?;
+    let copied: i64 = conn.query_row("SELECT COUNT(*) FROM play_time_new", [], |row| row.get(0))?;
+
+    if original != copied {
+        return Err(Error::Internal(format!(
+            "play_time rebuild copied {} of {} rows",
+            copied, original
+        )));
+    }
+
+    conn.execute_batch(
+        r#"
+        DROP TABLE play_time;
+
+        ALTER TABLE play_time_new RENAME TO play_time;
+
+        CREATE INDEX IF NOT EXISTS play_time_date_time_idx
+            ON play_time(date_time);
+
+        CREATE INDEX IF NOT EXISTS play_time_game_id_date_time_idx
+            ON play_time(game_id, date_time);
+
+        CREATE INDEX IF NOT EXISTS idx_play_time_migrated
+            ON play_time(migrated) WHERE migrated IS NULL;
+        "#,
+    )?;
+    Ok(())
+}
+
+fn migration_v13(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE game_total_goal(
+            game_id TEXT PRIMARY KEY,
+            target_seconds INTEGER NOT NULL,
+            FOREIGN KEY (game_id) REFERENCES game_dict(game_id)
+        );
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Mark names chosen by the user so automatic tracking doesn't overwrite
+/// them.
+fn migration_v14(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "ALTER TABLE game_dict ADD COLUMN is_user_set INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+    Ok(())
+}
+
+fn migration_v15(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE game_metadata(
+            game_id TEXT NOT NULL,
+            key TEXT NOT NULL,
+            value TEXT NOT NULL,
+            PRIMARY KEY (game_id, key),
+            FOREIGN KEY (game_id) REFERENCES game_dict(game_id)
+        );
+        "#,
+    )?;
+    Ok(())
+}
+
+fn migration_v16(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE day_note(
+            date TEXT PRIMARY KEY,
+            note TEXT NOT NULL
+        );
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Idempotency keys of `add_time` calls that were already recorded.
+fn migration_v17(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE applied_keys(
+            key TEXT PRIMARY KEY,
+            applied_at TEXT NOT NULL
+        );
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Make `date_time`/`duration` the only session columns.
+///
+/// Values written to `date`/`time` by migrations v9-v17 take precedence, then
+/// those columns are dropped.
+fn migration_v18(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        UPDATE play_time
+        SET date_time = COALESCE(date, date_time),
+            duration = COALESCE(time, duration);
+
+        ALTER TABLE play_time DROP COLUMN date;
+        ALTER TABLE play_time DROP COLUMN time;
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Start time of the original session for rows produced by splitting it at
+/// midnight.
+fn migration_v19(conn: &Connection) -> Result<()> {
+    conn.execute("ALTER TABLE play_time ADD COLUMN split_from TEXT", [])?;
+    Ok(())
+}
+
+/// Per-database preferences read by the core, stored as text.
+fn migration_v20(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE settings(
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Let a session checksum identify a `play_time` row of a game. Duplicates
+/// already stored keep the checksum only on their oldest row.
+fn migration_v21(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        UPDATE play_time SET checksum = NULL
+        WHERE checksum IS NOT NULL
+          AND id NOT IN (
+            SELECT MIN(id) FROM play_time
+            WHERE checksum IS NOT NULL
+            GROUP BY game_id, checksum
+          );
+
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_play_time_game_id_checksum
+            ON play_time(game_id, checksum) WHERE checksum IS NOT NULL;
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Playtime budgets per period, for one game or all games when `game_id` is
+/// NULL.
+fn migration_v22(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE goals(
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            game_id TEXT,
+            period TEXT NOT NULL,
+            target_seconds INTEGER NOT NULL,
+            FOREIGN KEY (game_id) REFERENCES game_dict(game_id)
+        );
+
+        CREATE UNIQUE INDEX idx_goals_scope
+            ON goals(COALESCE(game_id, ''), period);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Number of `play_time` rows an idempotency key recorded, returned again on
+/// retries. Keys applied before this column existed report zero.
+fn migration_v23(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "ALTER TABLE applied_keys ADD COLUMN row_count INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+    Ok(())
+}
+
+fn is_reversible(version: i32) -> bool {
+    MIGRATIONS
+        .iter()
+        .any(|migration| migration.version == version && migration.reversible)
+}
+
+fn revert_migration(conn: &Connection, version: i32) -> Result<()> {
+    match version {
+        7 => revert_v7(conn),
+        // Orphaned checksums are gone for good; there is no schema to undo
+        8 => Ok(()),
+        19 => revert_v19(conn),
+        20 => revert_v20(conn),
+        21 => revert_v21(conn),
+        22 => revert_v22(conn),
+        23 => revert_v23(conn),
+        _ => Err(Error::Internal(format!(
+            "No down migration for version: {}",
+            version
+        ))),
+    }
+}
+
+fn revert_v7(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        DROP INDEX IF EXISTS idx_overall_time_game_id;
+        DROP INDEX IF EXISTS idx_game_dict_game_id;
+        DROP INDEX IF EXISTS idx_play_time_migrated;
+        DROP INDEX IF EXISTS idx_game_file_checksum_game_id;
+        DROP INDEX IF EXISTS idx_game_file_checksum_composite;
+        "#,
+    )?;
+    Ok(())
+}
+
+fn revert_v19(conn: &Connection) -> Result<()> {
+    conn.execute("ALTER TABLE play_time DROP COLUMN split_from", [])?;
+    Ok(())
+}
+
+fn revert_v20(conn: &Connection) -> Result<()> {
+    conn.execute("DROP TABLE settings", [])?;
+    Ok(())
+}
+
+fn revert_v21(conn: &Connection) -> Result<()> {
+    conn.execute("DROP INDEX IF EXISTS idx_play_time_game_id_checksum", [])?;
+    Ok(())
+}
+
+fn revert_v22(conn: &Connection) -> Result<()> {
+    conn.execute("DROP TABLE goals", [])?;
+    Ok(())
+}
+
+fn revert_v23(conn: &Connection) -> Result<()> {
+    conn.execute("ALTER TABLE applied_keys DROP COLUMN row_count", [])?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use rusqlite::Connection;
@@ -216,6 +840,13 @@ mod tests {
         "overall_time",
         "game_dict",
         "game_file_checksum",
+        "stats_snapshot",
+        "game_total_goal",
+        "game_metadata",
+        "day_note",
+        "applied_keys",
+        "settings",
+        "goals",
         "migration",
     ];
 
@@ -243,6 +874,78 @@ mod tests {
             column_exists(&conn, "play_time", "migrated"),
             "play_time should have migrated column"
         );
+
+        for column in [
+            "id",
+            "date_time",
+            "duration",
+            "checksum",
+            "raw_duration",
+            "split_from",
+        ] {
+            assert!(
+                column_exists(&conn, "play_time", column),
+                "play_time should have {} column",
+                column
+            );
+        }
+
+        for column in ["date", "time"] {
+            assert!(
+                !column_exists(&conn, "play_time", column),
+                "play_time should not have {} column",
+                column
+            );
+        }
+
+        assert!(
+            column_exists(&conn, "game_dict", "is_user_set"),
+            "game_dict should have is_user_set column"
+        );
+    }
+
+    #[test]
+    fn test_list_covers_every_version() {
+        let migrations = list();
+
+        assert_eq!(migrations.len(), SCHEMA_VERSION as usize);
+        for (index, migration) in migrations.iter().enumerate() {
+            assert_eq!(migration.version, index as i32 + 1);
+            assert!(!migration.description.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_non_destructive_migration_keeps_row_counts() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        ensure_migration_table(&conn).unwrap();
+
+        for version in 1..=13 {
+            apply_migration(&mut conn, version).unwrap();
+        }
+
+        conn.execute_batch(
+            r#"
+            INSERT INTO game_dict (game_id, name) VALUES ('123', 'Test Game');
+            INSERT INTO play_time (game_id, date, time) VALUES ('123', '2024-01-01T10:00:00', 600);
+            INSERT INTO overall_time (game_id, duration) VALUES ('123', 600);
+            "#,
+        )
+        .unwrap();
+
+        let before = table_row_counts(&conn).unwrap();
+        apply_migration(&mut conn, 14).unwrap();
+        let after = table_row_counts(&conn).unwrap();
+
+        assert_eq!(before["play_time"], 1);
+        assert_eq!(after["play_time"], 1);
+        assert!(tables_with_lost_rows(&before, &after).is_empty());
+
+        conn.execute("DELETE FROM play_time", []).unwrap();
+        assert_eq!(
+            tables_with_lost_rows(&after, &table_row_counts(&conn).unwrap()),
+            vec!["play_time".to_string()]
+        );
     }
 
     #[test]
@@ -301,6 +1004,122 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_backup_before_migration() {
+        let dir = std::env::temp_dir().join(format!("test_backup_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = crate::db::Database::new(dir.join("storage.db")).unwrap();
+
+        // Nothing to protect in a brand-new file
+        assert_eq!(db.backup_before_migration().unwrap(), None);
+
+        db.with_connection(|conn| {
+            ensure_migration_table(conn)?;
+            for version in 1..=7 {
+                apply_migration(conn, version)?;
+            }
+            conn.execute(
+                "INSERT INTO play_time (date_time, duration, game_id)
+                 VALUES ('2024-01-01T10:00:00', 600, '123')",
+                [],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        let backup_path = db.backup_before_migration().unwrap().unwrap();
+        assert_eq!(backup_path, dir.join("storage.db.bak-7"));
+
+        db.with_connection(run_migrations).unwrap();
+        assert_eq!(db.backup_before_migration().unwrap(), None);
+
+        let backup = Connection::open(&backup_path).unwrap();
+        assert_eq!(get_schema_version(&backup).unwrap(), 7);
+        let rows: i64 = backup
+            .query_row("SELECT COUNT(*) FROM play_time", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(rows, 1);
+
+        drop(backup);
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_step_down_to_v6() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run_migrations_to(&mut conn, 8).unwrap();
+
+        assert_eq!(get_schema_version(&conn).unwrap(), 8);
+        assert!(index_exists(&conn, "idx_overall_time_game_id"));
+        assert!(index_exists(&conn, "idx_game_file_checksum_composite"));
+
+        apply_down_migration(&mut conn, 8).unwrap();
+        assert_eq!(get_schema_version(&conn).unwrap(), 7);
+        assert!(index_exists(&conn, "idx_overall_time_game_id"));
+
+        run_migrations_to(&mut conn, 6).unwrap();
+        assert_eq!(get_schema_version(&conn).unwrap(), 6);
+        for index in [
+            "idx_overall_time_game_id",
+            "idx_game_dict_game_id",
+            "idx_play_time_migrated",
+            "idx_game_file_checksum_game_id",
+            "idx_game_file_checksum_composite",
+        ] {
+            assert!(!index_exists(&conn, index), "{} should be dropped", index);
+        }
+        assert!(index_exists(&conn, "play_time_date_time_idx"));
+        assert!(index_exists(&conn, "play_time_game_id_date_time_idx"));
+
+        // And back up again
+        run_migrations(&mut conn).unwrap();
+        assert_eq!(get_schema_version(&conn).unwrap(), SCHEMA_VERSION);
+        assert!(index_exists(&conn, "idx_overall_time_game_id"));
+    }
+
+    #[test]
+    fn test_step_down_stops_at_irreversible_migration() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run_migrations(&mut conn).unwrap();
+
+        run_migrations_to(&mut conn, 18).unwrap();
+        assert!(!table_exists(&conn, "settings"));
+        assert!(!column_exists(&conn, "play_time", "split_from"));
+
+        let result = run_migrations_to(&mut conn, 6);
+        assert!(matches!(result, Err(Error::InvalidInput(_))));
+        assert_eq!(get_schema_version(&conn).unwrap(), 18);
+
+        assert!(apply_down_migration(&mut conn, 17).is_err());
+    }
+
+    #[test]
+    fn test_checksum_index_clears_duplicates() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run_migrations_to(&mut conn, 20).unwrap();
+
+        for game_id in ["123", "123", "456"] {
+            conn.execute(
+                "INSERT INTO play_time (date_time, duration, game_id, checksum)
+                 VALUES ('2024-01-01T10:00:00', 600, ?1, 'abc')",
+                [game_id],
+            )
+            .unwrap();
+        }
+
+        run_migrations(&mut conn).unwrap();
+        assert!(index_exists(&conn, "idx_play_time_game_id_checksum"));
+
+        let kept: Vec<(i64, String)> = conn
+            .prepare("SELECT id, game_id FROM play_time WHERE checksum IS NOT NULL ORDER BY id")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<std::result::Result<_, _>>()
+            .unwrap();
+        assert_eq!(kept, vec![(1, "123".into()), (3, "456".into())]);
+    }
+
     #[test]
     fn test_migration_atomicity() {
         let mut conn = Connection::open_in_memory().unwrap();
@@ -317,6 +1136,104 @@ mod tests {
         assert!(column_exists(&conn, "play_time", "migrated"));
     }
 
+    #[test]
+    fn test_play_time_id_rebuild_preserves_rows() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        ensure_migration_table(&conn).unwrap();
+
+        for v in 1..=11 {
+            apply_migration(&mut conn, v).unwrap();
+        }
+
+        conn.execute_batch(
+            r#"
+            INSERT INTO play_time (date_time, duration, game_id)
+                VALUES ('2024-01-03T10:00:00', 300, 'b');
+            INSERT INTO play_time (date_time, duration, game_id, migrated)
+                VALUES ('2024-01-01T10:00:00', 100, 'a', 'legacy');
+            INSERT INTO play_time (date, time, game_id, raw_duration)
+                VALUES ('2024-01-02T10:00:00', 200, 'a', 250);
+            "#,
+        )
+        .unwrap();
+
+        apply_migration(&mut conn, 12).unwrap();
+
+        let rows: Vec<(i64, String, i64)> = conn
+            .prepare("SELECT id, game_id, COALESCE(time, duration) FROM play_time ORDER BY id")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<std::result::Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(
+            rows,
+            vec![
+                (1, "b".to_string(), 300),
+                (2, "a".to_string(), 100),
+                (3, "a".to_string(), 200),
+            ]
+        );
+
+        let migrated: Option<String> = conn
+            .query_row("SELECT migrated FROM play_time WHERE id = 2", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(migrated.as_deref(), Some("legacy"));
+
+        assert!(index_exists(&conn, "play_time_game_id_date_time_idx"));
+    }
+
+    #[test]
+    fn test_session_columns_folded() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        ensure_migration_table(&conn).unwrap();
+
+        for v in 1..=17 {
+            apply_migration(&mut conn, v).unwrap();
+        }
+
+        conn.execute_batch(
+            r#"
+            INSERT INTO play_time (game_id, date_time, duration)
+                VALUES ('a', '2024-01-01T10:00:00', 100);
+            INSERT INTO play_time (game_id, date, time, raw_duration)
+                VALUES ('b', '2024-01-02T10:00:00', 200, 250);
+            "#,
+        )
+        .unwrap();
+
+        apply_migration(&mut conn, 18).unwrap();
+
+        let rows: Vec<(String, String, i64)> = conn
+            .prepare("SELECT game_id, date_time, duration FROM play_time ORDER BY id")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<std::result::Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(
+            rows,
+            vec![
+                ("a".to_string(), "2024-01-01T10:00:00".to_string(), 100),
+                ("b".to_string(), "2024-01-02T10:00:00".to_string(), 200),
+            ]
+        );
+    }
+
+    fn index_exists(conn: &Connection, index_name: &str) -> bool {
+        conn.query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master
+             WHERE type = 'index' AND name = ?1",
+            [index_name],
+            |row| row.get(0),
+        )
+        .unwrap_or(false)
+    }
+
     fn table_exists(conn: &Connection, table_name: &str) -> bool {
         conn.query_row(
             "SELECT COUNT(*) > 0 FROM sqlite_master
@@ -2,5 +2,5 @@ pub mod connection;
 pub mod dao;
 pub mod migrations;
 
-pub use connection::Database;
-pub use dao::{GamesDao, StatisticsDao, TimeTrackingDao};
+pub use connection::{Database, WalCheckpoint};
+pub use dao::{GamesDao, GoalsDao, SettingsDao, StatisticsDao, TimeTrackingDao};
@@ -1,18 +1,26 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use crate::db::{Database, GamesDao};
+use chrono::{Duration, Local, NaiveDate};
+
+use crate::db::{Database, GamesDao, StatisticsDao};
 use crate::error::Result;
-use crate::models::{Game, GameChecksum, GameStatistics};
+use crate::models::{ChecksumAlgorithm, ChecksumMeta, Game, GameChecksum, GameStatistics};
+
+/// Number of most recent active days used to estimate playing pace
+const PACE_ACTIVE_DAYS: u32 = 30;
 
 #[derive(Clone)]
 pub struct GamesService {
     dao: GamesDao,
+    statistics: StatisticsDao,
 }
 
 impl GamesService {
     pub fn new(db: Arc<Database>) -> Self {
         Self {
-            dao: GamesDao::new(db),
+            dao: GamesDao::new(Arc::clone(&db)),
+            statistics: StatisticsDao::new(db),
         }
     }
 
@@ -26,11 +34,58 @@ impl GamesService {
         self.dao.get_all_games()
     }
 
+    /// Get all games with their totals, ordered by name
+    pub fn get_all_with_stats(&self) -> Result<Vec<GameStatistics>> {
+        self.dao.get_all_games_with_stats()
+    }
+
     /// Save a game in dictionary
     pub fn save(&self, game: &Game) -> Result<()> {
         self.dao.save_game(game)
     }
 
+    /// Rename a game, protecting the name from automatic updates
+    pub fn rename(&self, game_id: &str, name: &str) -> Result<()> {
+        self.dao.rename_game(game_id, name)
+    }
+
+    /// Find groups of games that share a name, optionally ignoring case
+    pub fn find_duplicate_names(&self, case_insensitive: bool) -> Result<Vec<Vec<Game>>> {
+        self.dao.find_duplicate_names(case_insensitive)
+    }
+
+    /// Save a game only if it is not in the dictionary yet
+    ///
+    /// Returns whether the game was inserted; existing names are kept.
+    pub fn save_if_absent(&self, game: &Game) -> Result<bool> {
+        self.dao.save_game_if_absent(game)
+    }
+
+    /// Store a metadata value for a game
+    pub fn set_metadata(&self, game_id: &str, key: &str, value: &str) -> Result<()> {
+        self.dao.set_metadata(game_id, key, value)
+    }
+
+    /// Get a single metadata value for a game
+    pub fn get_metadata(&self, game_id: &str, key: &str) -> Result<Option<String>> {
+        self.dao.get_metadata(game_id, key)
+    }
+
+    /// Get every metadata entry for a game
+    pub fn get_all_metadata(&self, game_id: &str) -> Result<HashMap<String, String>> {
+        self.dao.get_all_metadata(game_id)
+    }
+
+    /// Delete a game and everything recorded for it
+    pub fn delete(&self, game_id: &str) -> Result<()> {
+        self.dao.delete_game(game_id)
+    }
+
+    /// Combine a duplicate game entry into another, keeping its playtime
+    pub fn merge_games(&self, source_id: &str, target_id: &str) -> Result<()> {
+        self.dao.merge_games(source_id, target_id)
+    }
+
     /// Save game checksum
     pub fn save_checksum(&self, checksum: &GameChecksum) -> Result<()> {
         self.dao.save_game_checksum(checksum)
@@ -40,4 +95,138 @@ impl GamesService {
     pub fn get_checksums(&self, game_id: &str) -> Result<Vec<GameChecksum>> {
         self.dao.get_game_checksums(game_id)
     }
+
+    /// Delete one checksum of a game, returning whether it existed
+    pub fn delete_checksum(
+        &self,
+        game_id: &str,
+        checksum: &str,
+        algorithm: ChecksumAlgorithm,
+    ) -> Result<bool> {
+        self.dao.delete_checksum(game_id, checksum, algorithm)
+    }
+
+    /// Delete every checksum of a game, returning how many were removed
+    pub fn delete_all_checksums(&self, game_id: &str) -> Result<usize> {
+        self.dao.delete_all_checksums(game_id)
+    }
+
+    /// Find the games whose files hash to `checksum` under `algorithm`
+    pub fn find_by_checksum(
+        &self,
+        checksum: &str,
+        algorithm: ChecksumAlgorithm,
+    ) -> Result<Vec<Game>> {
+        self.dao.find_games_by_checksum(checksum, algorithm)
+    }
+
+    /// Get checksum algorithm, chunk size and update time without the digests
+    pub fn get_checksum_meta(&self, game_id: &str) -> Result<Vec<ChecksumMeta>> {
+        self.dao.get_checksum_meta(game_id)
+    }
+
+    /// Set the total playtime goal for a game, in seconds
+    pub fn set_total_time_goal(&self, game_id: &str, target_seconds: i64) -> Result<()> {
+        self.dao.set_total_time_goal(game_id, target_seconds)
+    }
+
+    /// Get the total playtime goal for a game, in seconds
+    pub fn get_total_time_goal(&self, game_id: &str) -> Result<Option<i64>> {
+        self.dao.get_total_time_goal(game_id)
+    }
+
+    /// Project the date a game's total playtime goal will be reached
+    ///
+    /// The pace is the average daily playtime over the last 30 active days.
+    /// Returns the date it was reached if the goal is already met, and `None`
+    /// when there is no goal or no recent playtime.
+    pub fn estimate_goal_eta(&self, game_id: &str) -> Result<Option<NaiveDate>> {
+        let Some(goal) = self.dao.get_total_time_goal(game_id)? else {
+            return Ok(None);
+        };
+
+        let total = self
+            .dao
+            .get_game_with_stats(game_id)?
+            .map_or(0, |stats| stats.total_time);
+
+        if total >= goal {
+            return self.statistics.get_milestone_date(game_id, goal);
+        }
+
+        let pace = self
+            .statistics
+            .get_recent_active_day_average(game_id, PACE_ACTIVE_DAYS)?
+            .unwrap_or(0.0);
+
+        if pace <= 0.0 {
+            return Ok(None);
+        }
+
+        let days_left = ((goal - total) as f64 / pace).ceil() as i64;
+
+        Ok(Some(Local::now().date_naive() + Duration::days(days_left)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use rusqlite::params;
+
+    use super::*;
+    use crate::db::migrations::run_migrations;
+
+    fn setup_test_db() -> Arc<Database> {
+        let db_path =
+            env::temp_dir().join(format!("test_games_service_{}.db", uuid::Uuid::new_v4()));
+        let db = Database::new(&db_path).unwrap();
+        db.with_connection(run_migrations).unwrap();
+
+        Arc::new(db)
+    }
+
+    fn insert_session(db: &Database, game_id: &str, days_ago: i64, time: i64) {
+        let date = Local::now().naive_local() - Duration::days(days_ago);
+
+        db.with_connection(|conn| {
+            conn.execute(
+                "INSERT OR IGNORE INTO game_dict (game_id, name) VALUES (?1, ?1)",
+                params![game_id],
+            )?;
+            conn.execute(
+                "INSERT INTO play_time (game_id, date_time, duration) VALUES (?1, ?2, ?3)",
+                params![game_id, date.format("%Y-%m-%dT%H:%M:%S").to_string(), time],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_estimate_goal_eta() {
+        let db = setup_test_db();
+        let service = GamesService::new(Arc::clone(&db));
+
+        for days_ago in 1..=3 {
+            insert_session(&db, "123", days_ago, 3600);
+        }
+
+        assert_eq!(service.estimate_goal_eta("123").unwrap(), None);
+
+        // 10h goal, 3h played, 1h/day pace -> 7 more days
+        service.set_total_time_goal("123", 36_000).unwrap();
+        assert_eq!(
+            service.estimate_goal_eta("123").unwrap(),
+            Some(Local::now().date_naive() + Duration::days(7))
+        );
+
+        // Already reached on the second session
+        service.set_total_time_goal("123", 7200).unwrap();
+        assert_eq!(
+            service.estimate_goal_eta("123").unwrap(),
+            Some(Local::now().date_naive() - Duration::days(2))
+        );
+    }
 }
@@ -0,0 +1,160 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Local};
+
+use crate::db::{Database, GoalsDao};
+use crate::error::{Error, Result};
+use crate::models::GoalProgress;
+use crate::utils::start_of_week;
+
+/// Weekly playtime budgets, per game or across all games when the game id
+/// is `None`
+#[derive(Clone)]
+pub struct GoalsService {
+    dao: GoalsDao,
+}
+
+impl GoalsService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            dao: GoalsDao::new(db),
+        }
+    }
+
+    /// Set the weekly playtime budget in seconds
+    pub fn set_weekly_goal(&self, game_id: Option<&str>, seconds: i64) -> Result<()> {
+        if seconds <= 0 {
+            return Err(Error::InvalidInput("Goal must be positive".into()));
+        }
+
+        self.dao.set_weekly_goal(game_id, seconds)
+    }
+
+    /// Get the weekly playtime budget in seconds
+    pub fn get_goal(&self, game_id: Option<&str>) -> Result<Option<i64>> {
+        self.dao.get_weekly_goal(game_id)
+    }
+
+    /// Compare a weekly goal with the playtime of an ISO week
+    ///
+    /// `week_offset` counts weeks back from the current one. Returns `None`
+    /// when no goal is set.
+    pub fn get_goal_progress(
+        &self,
+        game_id: Option<&str>,
+        week_offset: u32,
+    ) -> Result<Option<GoalProgress>> {
+        let Some(goal_seconds) = self.dao.get_weekly_goal(game_id)? else {
+            return Ok(None);
+        };
+
+        let start =
+            start_of_week(Local::now().date_naive()) - Duration::weeks(i64::from(week_offset));
+        let played_seconds =
+            self.dao
+                .get_played_seconds(game_id, start, start + Duration::weeks(1))?;
+
+        Ok(Some(GoalProgress {
+            goal_seconds,
+            played_seconds,
+            remaining: (goal_seconds - played_seconds).max(0),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use rusqlite::params;
+
+    use super::*;
+    use crate::db::migrations::run_migrations;
+
+    fn setup_test_db() -> Arc<Database> {
+        let db_path =
+            env::temp_dir().join(format!("test_goals_service_{}.db", uuid::Uuid::new_v4()));
+        let db = Database::new(&db_path).unwrap();
+        db.with_connection(run_migrations).unwrap();
+
+        Arc::new(db)
+    }
+
+    /// Insert a session at noon, `weeks_ago` weeks before this week's Monday
+    fn insert_session(db: &Database, game_id: &str, weeks_ago: i64, time: i64) {
+        let date = start_of_week(Local::now().date_naive()) - Duration::weeks(weeks_ago);
+        let date_time = date.and_hms_opt(12, 0, 0).unwrap();
+
+        db.with_connection(|conn| {
+            conn.execute(
+                "INSERT OR IGNORE INTO game_dict (game_id, name) VALUES (?1, ?1)",
+                params![game_id],
+            )?;
+            conn.execute(
+                "INSERT INTO play_time (game_id, date_time, duration) VALUES (?1, ?2, ?3)",
+                params![
+                    game_id,
+                    date_time.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                    time
+                ],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_all_games_goal_progress() {
+        let db = setup_test_db();
+        let service = GoalsService::new(Arc::clone(&db));
+
+        insert_session(&db, "123", 0, 3600);
+        insert_session(&db, "456", 0, 1800);
+        insert_session(&db, "123", 1, 7200);
+
+        assert_eq!(service.get_goal_progress(None, 0).unwrap(), None);
+
+        service.set_weekly_goal(None, 10_800).unwrap();
+        assert_eq!(service.get_goal(None).unwrap(), Some(10_800));
+        assert_eq!(
+            service.get_goal_progress(None, 0).unwrap(),
+            Some(GoalProgress {
+                goal_seconds: 10_800,
+                played_seconds: 5400,
+                remaining: 5400,
+            })
+        );
+        assert_eq!(
+            service
+                .get_goal_progress(None, 1)
+                .unwrap()
+                .unwrap()
+                .played_seconds,
+            7200
+        );
+
+        assert!(service.set_weekly_goal(None, 0).is_err());
+    }
+
+    #[test]
+    fn test_per_game_goal_progress() {
+        let db = setup_test_db();
+        let service = GoalsService::new(Arc::clone(&db));
+
+        insert_session(&db, "123", 0, 3600);
+        insert_session(&db, "123", 0, 1800);
+        insert_session(&db, "456", 0, 7200);
+
+        service.set_weekly_goal(Some("123"), 3600).unwrap();
+        assert_eq!(service.get_goal(None).unwrap(), None);
+        assert_eq!(
+            service.get_goal_progress(Some("123"), 0).unwrap(),
+            Some(GoalProgress {
+                goal_seconds: 3600,
+                played_seconds: 5400,
+                remaining: 0,
+            })
+        );
+        assert_eq!(service.get_goal_progress(Some("456"), 0).unwrap(), None);
+    }
+}
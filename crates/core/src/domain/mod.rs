@@ -1,7 +1,11 @@
 pub mod games;
+pub mod goals;
 pub mod statistics;
 pub mod time_tracking;
+pub mod users;
 
 pub use games::GamesService;
+pub use goals::GoalsService;
 pub use statistics::StatisticsService;
-pub use time_tracking::TimeTrackingService;
+pub use time_tracking::{SessionRecordedCallback, TimeTrackingService};
+pub use users::UserStore;
@@ -1,23 +1,48 @@
+use std::collections::HashMap;
+use std::io::Write;
 use std::sync::Arc;
 
-use chrono::NaiveDate;
+use chrono::{Days, Duration, Local, NaiveDate, NaiveDateTime};
 
-use crate::db::{Database, StatisticsDao};
-use crate::error::Result;
-use crate::models::{DailyStatistics, GameStatistics};
+use crate::db::{Database, GamesDao, StatisticsDao, TimeTrackingDao};
+use crate::error::{Error, Result};
+use crate::models::{
+    AdjustmentSummary, DailyStatistics, Game, GameStatistics, LibraryTotals, PlaySession,
+    SessionInfo, StatisticsSnapshot, StreakInfo, WeeklyStatistics,
+};
+use crate::utils::start_of_week;
 
 #[derive(Clone)]
 pub struct StatisticsService {
     dao: StatisticsDao,
+    games: GamesDao,
+    sessions: TimeTrackingDao,
+    day_start_hour: u32,
 }
 
 impl StatisticsService {
     pub fn new(db: Arc<Database>) -> Self {
         Self {
-            dao: StatisticsDao::new(db),
+            dao: StatisticsDao::new(Arc::clone(&db)),
+            games: GamesDao::new(Arc::clone(&db)),
+            sessions: TimeTrackingDao::new(db),
+            day_start_hour: 0,
         }
     }
 
+    /// Start each "daily" bucket at `hour` (0-23) instead of midnight
+    pub fn with_day_start_hour(mut self, hour: u32) -> Result<Self> {
+        if hour > 23 {
+            return Err(Error::InvalidInput(
+                "Day start hour must be between 0 and 23".into(),
+            ));
+        }
+
+        self.day_start_hour = hour;
+
+        Ok(self)
+    }
+
     /// Get overall statistics for all games
     pub fn get_overall(&self) -> Result<Vec<GameStatistics>> {
         self.dao.get_overall_statistics()
@@ -29,11 +54,872 @@ impl StatisticsService {
         start_date: NaiveDate,
         end_date: NaiveDate,
     ) -> Result<Vec<DailyStatistics>> {
-        self.dao.get_daily_statistics(start_date, end_date)
+        self.dao
+            .get_daily_statistics_with_rollover(start_date, end_date, self.day_start_hour)
     }
 
     /// Get statistics for a specific game
     pub fn get_for_game(&self, game_id: &str) -> Result<Option<GameStatistics>> {
         self.dao.get_game_statistics(game_id)
     }
+
+    /// Get session counts per duration bucket, plus an overflow count
+    pub fn get_session_length_histogram(
+        &self,
+        game_id: Option<&str>,
+        buckets: &[i64],
+    ) -> Result<Vec<i64>> {
+        self.dao.get_session_length_histogram(game_id, buckets)
+    }
+
+    /// Store the current library totals as a trend point
+    pub fn take_snapshot(&self) -> Result<()> {
+        self.dao.take_snapshot()
+    }
+
+    /// Get stored snapshots taken within a date range
+    pub fn get_snapshots(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<StatisticsSnapshot>> {
+        self.dao.get_snapshots(start_date, end_date)
+    }
+
+    /// Get the date on which a game's total playtime first reached a threshold
+    pub fn get_milestone_date(
+        &self,
+        game_id: &str,
+        threshold_secs: i64,
+    ) -> Result<Option<NaiveDate>> {
+        self.dao.get_milestone_date(game_id, threshold_secs)
+    }
+
+    /// Get the last played timestamp for many games at once
+    ///
+    /// An empty slice returns every game that has sessions.
+    pub fn get_last_played_map(&self, game_ids: &[&str]) -> Result<HashMap<String, NaiveDateTime>> {
+        self.dao.get_last_played_map(game_ids)
+    }
+
+    /// Get whole days since each game was last played, most neglected first
+    ///
+    /// Games that were never played are not included.
+    pub fn get_days_since_last_play(&self) -> Result<Vec<(Game, i64)>> {
+        let last_played = self.dao.get_last_played_map(&[])?;
+        let today = Local::now().date_naive();
+
+        let mut result: Vec<(Game, i64)> = self
+            .games
+            .get_all_games()?
+            .into_iter()
+            .filter_map(|game| {
+                let days = (today - last_played.get(&game.id)?.date()).num_days();
+                Some((game, days))
+            })
+            .collect();
+
+        result.sort_by(|(a_game, a_days), (b_game, b_days)| {
+            b_days
+                .cmp(a_days)
+                .then_with(|| a_game.name.cmp(&b_game.name))
+        });
+
+        Ok(result)
+    }
+
+    /// Get recorded versus raw wall-clock time for a game
+    pub fn get_adjustment_summary(&self, game_id: &str) -> Result<AdjustmentSummary> {
+        self.dao.get_adjustment_summary(game_id)
+    }
+
+    /// Get combined playtime for all games whose name matches a LIKE pattern
+    pub fn get_total_for_name_pattern(&self, pattern: &str) -> Result<i64> {
+        self.dao.get_total_for_name_pattern(pattern)
+    }
+
+    /// Get every session on a day across all games, in start order
+    pub fn get_sessions_for_day(&self, date: NaiveDate) -> Result<Vec<(Game, PlaySession)>> {
+        self.dao.get_sessions_for_day(date)
+    }
+
+    /// Get a timeline of every session on a day across all games, oldest
+    /// first
+    pub fn get_sessions_on_date(&self, date: NaiveDate) -> Result<Vec<(Game, SessionInfo)>> {
+        self.dao.get_sessions_on_date(date)
+    }
+
+    /// Stream every session to `writer` as CSV with the columns game_id,
+    /// game_name, date_time, duration and migrated
+    pub fn export_sessions_csv(&self, mut writer: impl Write) -> Result<()> {
+        self.dao.export_sessions_csv(&mut writer)
+    }
+
+    /// Get sessions dated earlier than a previously recorded session, which
+    /// indicates the device clock was moved backward
+    pub fn find_clock_anomalies(&self, game_id: &str) -> Result<Vec<PlaySession>> {
+        self.dao.get_clock_anomalies(game_id)
+    }
+
+    /// Find games whose longest session exceeds `threshold_secs_per_session`,
+    /// paired with that session, longest first
+    ///
+    /// These usually come from the device sleeping mid-session and are
+    /// candidates for review and capping.
+    pub fn find_inflated_totals(
+        &self,
+        threshold_secs_per_session: i64,
+    ) -> Result<Vec<(Game, PlaySession)>> {
+        if threshold_secs_per_session <= 0 {
+            return Err(Error::InvalidInput("Threshold must be positive".into()));
+        }
+
+        let threshold = threshold_secs_per_session as f64;
+
+        Ok(self
+            .dao
+            .get_longest_session_per_game()?
+            .into_iter()
+            .filter(|(_, session)| session.duration > threshold)
+            .collect())
+    }
+
+    /// Get library-wide playtime, game and session counts
+    pub fn get_library_totals(&self) -> Result<LibraryTotals> {
+        self.dao.get_library_totals()
+    }
+
+    /// Get the number of consecutive days played up to today or yesterday
+    pub fn get_current_streak(&self, game_id: Option<&str>) -> Result<u32> {
+        let today = Local::now().date_naive();
+
+        Ok(count_streak(&self.dao.get_play_days(game_id)?, today))
+    }
+
+    /// Get the current and longest runs of consecutive days played across
+    /// all games
+    pub fn get_streaks(&self) -> Result<StreakInfo> {
+        let today = Local::now().date_naive();
+        let days = self.dao.get_play_days(None)?;
+
+        Ok(StreakInfo {
+            current_streak: count_streak(&days, today),
+            longest_streak: longest_streak(&days),
+            last_played: days.first().copied(),
+        })
+    }
+
+    /// Get the current streak length if it breaks unless there is play today
+    ///
+    /// Returns `None` when today already has a session or the last play was
+    /// before yesterday.
+    pub fn streak_at_risk(&self, game_id: Option<&str>) -> Result<Option<u32>> {
+        let today = Local::now().date_naive();
+        let days = self.dao.get_play_days(game_id)?;
+
+        if days.first() != today.checked_sub_days(Days::new(1)).as_ref() {
+            return Ok(None);
+        }
+
+        Ok(Some(count_streak(&days, today)))
+    }
+
+    /// Get per-game playtime totals for a date range, most played first
+    pub fn get_range_summary(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<(Game, i64)>> {
+        self.dao.get_range_summary(start_date, end_date)
+    }
+
+    /// Get per-game playtime for each calendar week in a date range
+    pub fn get_weekly(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<WeeklyStatistics>> {
+        self.dao.get_weekly_statistics(start_date, end_date)
+    }
+
+    /// Get per-game playtime from this week's Monday through today
+    pub fn get_current_week_stats(&self) -> Result<Vec<(Game, i64)>> {
+        let today = Local::now().date_naive();
+
+        self.dao.get_range_summary(start_of_week(today), today)
+    }
+
+    /// Get average playtime per day on days with any play
+    pub fn get_average_daily_playtime(&self, game_id: Option<&str>) -> Result<Option<f64>> {
+        self.dao.get_average_daily_playtime(game_id)
+    }
+
+    /// Get the share of playtime (0.0-1.0) that was imported or migrated
+    /// rather than tracked live
+    pub fn get_migrated_fraction(&self, game_id: Option<&str>) -> Result<f64> {
+        self.dao.get_migrated_fraction(game_id)
+    }
+
+    /// Get the average number of sessions on days with any play
+    pub fn get_sessions_per_active_day(&self, game_id: Option<&str>) -> Result<Option<f64>> {
+        self.dao.get_sessions_per_active_day(game_id)
+    }
+
+    /// Get games played at least once in a date range, ordered by name
+    pub fn get_games_played_in_range(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<Game>> {
+        self.dao.get_games_played_in_range(start_date, end_date)
+    }
+
+    /// Get the median session length for a game
+    pub fn get_median_session_length(&self, game_id: &str) -> Result<Option<f64>> {
+        self.dao.get_median_session_length(game_id)
+    }
+
+    /// Get the mean gap in days between the starts of a game's consecutive
+    /// sessions
+    ///
+    /// Returns `None` when the game has fewer than two sessions.
+    pub fn get_average_session_gap(&self, game_id: &str) -> Result<Option<f64>> {
+        let mut starts: Vec<f64> = self
+            .sessions
+            .get_game_sessions(game_id)?
+            .iter()
+            .map(|session| session.started_at)
+            .collect();
+
+        if starts.len() < 2 {
+            return Ok(None);
+        }
+
+        starts.sort_unstable_by(f64::total_cmp);
+
+        let total_gap: f64 = starts.windows(2).map(|pair| pair[1] - pair[0]).sum();
+        let gaps = (starts.len() - 1) as f64;
+
+        Ok(Some(total_gap / gaps / 86_400.0))
+    }
+
+    /// Get the day with the most playtime and its total
+    pub fn get_busiest_day(&self) -> Result<Option<(NaiveDate, i64)>> {
+        self.dao.get_busiest_day()
+    }
+
+    /// Get the full per-game breakdown of the day with the most playtime
+    pub fn get_busiest_day_detail(&self) -> Result<Option<DailyStatistics>> {
+        let Some((day, _)) = self.dao.get_busiest_day()? else {
+            return Ok(None);
+        };
+
+        Ok(self.dao.get_daily_statistics(day, day)?.into_iter().next())
+    }
+
+    /// Get the most played games within a date range, inclusive
+    pub fn get_top_games(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        limit: u32,
+    ) -> Result<Vec<GameStatistics>> {
+        self.dao.get_top_games(start, end, limit)
+    }
+
+    /// Get the most frequently launched games by session count
+    pub fn get_top_games_by_sessions(&self, limit: usize) -> Result<Vec<GameStatistics>> {
+        self.dao.get_top_games_by_sessions(limit)
+    }
+
+    /// Get each game's playtime per month of a year
+    pub fn get_monthly_per_game(&self, year: i32) -> Result<HashMap<String, [i64; 12]>> {
+        self.dao.get_monthly_per_game(year)
+    }
+
+    /// Get total playtime per calendar year, oldest first
+    pub fn get_yearly_totals(&self) -> Result<Vec<(i32, i64)>> {
+        self.dao.get_yearly_totals()
+    }
+
+    /// Get the share of total playtime (0.0-1.0) held by the top 20% of
+    /// played games
+    ///
+    /// Returns 0.0 when nothing was played.
+    pub fn get_playtime_concentration(&self) -> Result<f64> {
+        let mut totals: Vec<i64> = self
+            .dao
+            .get_overall_statistics()?
+            .iter()
+            .map(|stats| stats.total_time)
+            .collect();
+
+        let total: i64 = totals.iter().sum();
+
+        if total <= 0 {
+            return Ok(0.0);
+        }
+
+        totals.sort_unstable_by(|a, b| b.cmp(a));
+
+        let top_count = totals.len().div_ceil(5);
+        let top_total: i64 = totals.iter().take(top_count).sum();
+
+        Ok(top_total as f64 / total as f64)
+    }
+
+    /// Attach a journal note to a day
+    pub fn set_day_note(&self, date: NaiveDate, note: &str) -> Result<()> {
+        self.dao.set_day_note(date, note)
+    }
+
+    /// Get the journal note for a day, if any
+    pub fn get_day_note(&self, date: NaiveDate) -> Result<Option<String>> {
+        self.dao.get_day_note(date)
+    }
+
+    /// Get the first and last days with play and the whole days between them
+    pub fn get_activity_span(&self) -> Result<Option<(NaiveDate, NaiveDate, i64)>> {
+        Ok(self
+            .dao
+            .get_activity_span()?
+            .map(|(first, last)| (first, last, (last - first).num_days())))
+    }
+
+    /// Get a game's wall-clock playtime with overlapping sessions merged
+    pub fn get_total_distinct_time(&self, game_id: &str) -> Result<i64> {
+        self.dao.get_total_distinct_time(game_id)
+    }
+
+    /// Get total playtime for every day in a range, with zero for days
+    /// without sessions
+    pub fn get_daily_totals(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, i64)>> {
+        let totals: HashMap<NaiveDate, i64> = self
+            .dao
+            .get_daily_totals(start, end, self.day_start_hour)?
+            .into_iter()
+            .collect();
+
+        Ok(start
+            .iter_days()
+            .take_while(|day| *day <= end)
+            .map(|day| (day, totals.get(&day).copied().unwrap_or(0)))
+            .collect())
+    }
+
+    /// Find the `window_days`-long run of days with the most playtime
+    ///
+    /// Returns the window's first and last day and its total, or `None` when
+    /// nothing was played. Ties go to the earliest window.
+    pub fn get_peak_window(&self, window_days: i64) -> Result<Option<(NaiveDate, NaiveDate, i64)>> {
+        if window_days < 1 {
+            return Err(Error::InvalidInput(
+                "Window must be at least one day".into(),
+            ));
+        }
+
+        let Some((first, last)) = self.dao.get_activity_span()? else {
+            return Ok(None);
+        };
+
+        // A day start after midnight can move early sessions to the day before
+        let totals =
+            self.dao
+                .get_daily_totals(first - Duration::days(1), last, self.day_start_hour)?;
+
+        let Some(&(start, _)) = totals.first() else {
+            return Ok(None);
+        };
+
+        let mut daily = Vec::new();
+        for (day, total) in totals {
+            daily.resize((day - start).num_days() as usize, 0);
+            daily.push(total);
+        }
+
+        let window = (window_days as usize).min(daily.len());
+        let mut sum: i64 = daily[..window].iter().sum();
+        let mut best = (0, sum);
+
+        for end in window..daily.len() {
+            sum += daily[end] - daily[end - window];
+
+            if sum > best.1 {
+                best = (end + 1 - window, sum);
+            }
+        }
+
+        let window_start = start + Duration::days(best.0 as i64);
+
+        Ok(Some((
+            window_start,
+            window_start + Duration::days(window_days - 1),
+            best.1,
+        )))
+    }
+}
+
+/// Count consecutive days ending today or yesterday in a most-recent-first
+/// list of distinct days
+fn count_streak(days: &[NaiveDate], today: NaiveDate) -> u32 {
+    let Some(&latest) = days.first() else {
+        return 0;
+    };
+
+    if (today - latest).num_days() > 1 {
+        return 0;
+    }
+
+    let mut streak = 1;
+
+    for pair in days.windows(2) {
+        if (pair[0] - pair[1]).num_days() != 1 {
+            break;
+        }
+        streak += 1;
+    }
+
+    streak
+}
+
+/// Length of the longest run of consecutive days in a most-recent-first list
+/// of distinct days
+fn longest_streak(days: &[NaiveDate]) -> u32 {
+    let mut longest = u32::from(!days.is_empty());
+    let mut run = longest;
+
+    for pair in days.windows(2) {
+        run = if (pair[0] - pair[1]).num_days() == 1 {
+            run + 1
+        } else {
+            1
+        };
+        longest = longest.max(run);
+    }
+
+    longest
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use chrono::Duration;
+    use rusqlite::params;
+
+    use super::*;
+    use crate::db::migrations::run_migrations;
+
+    fn setup_test_db() -> Arc<Database> {
+        let db_path =
+            env::temp_dir().join(format!("test_stats_service_{}.db", uuid::Uuid::new_v4()));
+        let db = Database::new(&db_path).unwrap();
+        db.with_connection(run_migrations).unwrap();
+
+        Arc::new(db)
+    }
+
+    fn insert_session(db: &Database, game_id: &str, name: &str, date: NaiveDateTime, time: i64) {
+        db.with_connection(|conn| {
+            conn.execute(
+                "INSERT OR IGNORE INTO game_dict (game_id, name) VALUES (?1, ?2)",
+                params![game_id, name],
+            )?;
+            conn.execute(
+                "INSERT INTO play_time (game_id, date_time, duration) VALUES (?1, ?2, ?3)",
+                params![game_id, date.format("%Y-%m-%dT%H:%M:%S").to_string(), time],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_days_since_last_play() {
+        let db = setup_test_db();
+        let service = StatisticsService::new(Arc::clone(&db));
+        let now = Local::now().naive_local();
+
+        insert_session(&db, "1", "Recent", now - Duration::days(1), 600);
+        insert_session(&db, "2", "Stale", now - Duration::days(40), 600);
+        insert_session(&db, "2", "Stale", now - Duration::days(30), 600);
+        GamesDao::new(Arc::clone(&db))
+            .save_game(&Game::new("3", "Never Played"))
+            .unwrap();
+
+        let days = service.get_days_since_last_play().unwrap();
+
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].0.id, "2");
+        assert_eq!(days[0].1, 30);
+        assert_eq!(days[1].0.id, "1");
+        assert_eq!(days[1].1, 1);
+    }
+
+    #[test]
+    fn test_streak_at_risk() {
+        let db = setup_test_db();
+        let service = StatisticsService::new(Arc::clone(&db));
+        let noon = |days_ago: i64| {
+            (Local::now().date_naive() - Duration::days(days_ago))
+                .and_hms_opt(12, 0, 0)
+                .unwrap()
+        };
+
+        for days_ago in [1, 2, 3, 5] {
+            insert_session(&db, "1", "Daily", noon(days_ago), 600);
+        }
+        insert_session(&db, "2", "Today", noon(1), 600);
+        insert_session(&db, "2", "Today", noon(0), 600);
+
+        assert_eq!(service.streak_at_risk(Some("1")).unwrap(), Some(3));
+        assert_eq!(service.get_current_streak(Some("1")).unwrap(), 3);
+        assert_eq!(service.streak_at_risk(Some("2")).unwrap(), None);
+        assert_eq!(service.get_current_streak(Some("2")).unwrap(), 2);
+        assert_eq!(service.streak_at_risk(None).unwrap(), None);
+        assert_eq!(service.streak_at_risk(Some("3")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_get_streaks() {
+        let db = setup_test_db();
+        let service = StatisticsService::new(Arc::clone(&db));
+        let today = Local::now().date_naive();
+        let noon = |days_ago: i64| {
+            (today - Duration::days(days_ago))
+                .and_hms_opt(12, 0, 0)
+                .unwrap()
+        };
+
+        assert_eq!(
+            service.get_streaks().unwrap(),
+            StreakInfo {
+                current_streak: 0,
+                longest_streak: 0,
+                last_played: None,
+            }
+        );
+
+        // Four days in a row, then a single-day gap before the latest play
+        for days_ago in [2, 4, 5, 6, 7] {
+            insert_session(&db, "1", "Daily", noon(days_ago), 600);
+        }
+
+        assert_eq!(
+            service.get_streaks().unwrap(),
+            StreakInfo {
+                current_streak: 0,
+                longest_streak: 4,
+                last_played: Some(today - Duration::days(2)),
+            }
+        );
+
+        // Playing yesterday keeps the run going into today
+        insert_session(&db, "2", "Other", noon(1), 600);
+        assert_eq!(service.get_streaks().unwrap().current_streak, 2);
+
+        insert_session(&db, "1", "Daily", noon(0), 600);
+        assert_eq!(
+            service.get_streaks().unwrap(),
+            StreakInfo {
+                current_streak: 3,
+                longest_streak: 4,
+                last_played: Some(today),
+            }
+        );
+    }
+
+    #[test]
+    fn test_current_week_stats() {
+        let db = setup_test_db();
+        let service = StatisticsService::new(Arc::clone(&db));
+        let monday = start_of_week(Local::now().date_naive());
+        let at = |date: NaiveDate| date.and_hms_opt(0, 0, 0).unwrap();
+
+        insert_session(&db, "1", "Current", at(monday), 600);
+        insert_session(&db, "1", "Current", at(Local::now().date_naive()), 300);
+        insert_session(&db, "1", "Current", at(monday - Duration::days(1)), 1200);
+        insert_session(&db, "2", "Last Week", at(monday - Duration::days(7)), 900);
+
+        let week = service.get_current_week_stats().unwrap();
+
+        assert_eq!(week.len(), 1);
+        assert_eq!(week[0].0.id, "1");
+        assert_eq!(week[0].1, 900);
+    }
+
+    #[test]
+    fn test_busiest_day_detail() {
+        let db = setup_test_db();
+        let service = StatisticsService::new(Arc::clone(&db));
+        let at = |day: u32, hour: u32| {
+            NaiveDate::from_ymd_opt(2024, 1, day)
+                .and_then(|d| d.and_hms_opt(hour, 0, 0))
+                .unwrap()
+        };
+
+        assert!(service.get_busiest_day_detail().unwrap().is_none());
+
+        insert_session(&db, "1", "Long Day", at(1, 10), 5000);
+        insert_session(&db, "1", "Long Day", at(2, 10), 3000);
+        insert_session(&db, "2", "Evening", at(2, 20), 2500);
+        insert_session(&db, "2", "Evening", at(2, 22), 500);
+
+        assert_eq!(
+            service.get_busiest_day().unwrap(),
+            Some((NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(), 6000))
+        );
+
+        let detail = service.get_busiest_day_detail().unwrap().unwrap();
+        assert_eq!(detail.date, NaiveDate::from_ymd_opt(2024, 1, 2).unwrap());
+
+        let mut games: Vec<(&str, i64, usize)> = detail
+            .games
+            .iter()
+            .map(|game| (game.game.id.as_str(), game.time, game.sessions.len()))
+            .collect();
+        games.sort();
+        assert_eq!(games, vec![("1", 3000, 1), ("2", 3000, 2)]);
+    }
+
+    #[test]
+    fn test_playtime_concentration() {
+        let db = setup_test_db();
+        let service = StatisticsService::new(Arc::clone(&db));
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1)
+            .and_then(|d| d.and_hms_opt(12, 0, 0))
+            .unwrap();
+
+        assert_eq!(service.get_playtime_concentration().unwrap(), 0.0);
+
+        insert_session(&db, "1", "Favorite", date, 90_000);
+        insert_session(&db, "2", "Runner Up", date, 8_000);
+        for id in 3..=10 {
+            insert_session(&db, &id.to_string(), "Sampled", date, 250);
+        }
+
+        // Top 2 of 10 games hold 98,000 of 100,000 seconds
+        let concentration = service.get_playtime_concentration().unwrap();
+        assert!((concentration - 0.98).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_activity_span() {
+        let db = setup_test_db();
+        let service = StatisticsService::new(Arc::clone(&db));
+        let at = |month: u32, day: u32, hour: u32| {
+            NaiveDate::from_ymd_opt(2024, month, day)
+                .and_then(|d| d.and_hms_opt(hour, 0, 0))
+                .unwrap()
+        };
+
+        assert_eq!(service.get_activity_span().unwrap(), None);
+
+        insert_session(&db, "1", "Middle", at(2, 10, 12), 600);
+        insert_session(&db, "2", "Last", at(3, 1, 23), 600);
+        insert_session(&db, "3", "First", at(1, 15, 8), 600);
+
+        assert_eq!(
+            service.get_activity_span().unwrap(),
+            Some((
+                NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+                46
+            ))
+        );
+    }
+
+    #[test]
+    fn test_peak_window() {
+        let db = setup_test_db();
+        let service = StatisticsService::new(Arc::clone(&db));
+        let at = |day: u32| {
+            NaiveDate::from_ymd_opt(2024, 1, day)
+                .and_then(|d| d.and_hms_opt(12, 0, 0))
+                .unwrap()
+        };
+
+        assert_eq!(service.get_peak_window(7).unwrap(), None);
+
+        insert_session(&db, "1", "Steady", at(1), 1800);
+        for day in 10..=12 {
+            insert_session(&db, "1", "Steady", at(day), 7200);
+        }
+        insert_session(&db, "1", "Steady", at(25), 1800);
+
+        assert_eq!(
+            service.get_peak_window(3).unwrap(),
+            Some((
+                NaiveDate::from_ymd_opt(2024, 1, 10).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 1, 12).unwrap(),
+                21600
+            ))
+        );
+
+        let (start, end, total) = service.get_peak_window(7).unwrap().unwrap();
+        assert!(start <= NaiveDate::from_ymd_opt(2024, 1, 10).unwrap());
+        assert!(end >= NaiveDate::from_ymd_opt(2024, 1, 12).unwrap());
+        assert_eq!(total, 21600);
+
+        assert!(service.get_peak_window(0).is_err());
+    }
+
+    #[test]
+    fn test_get_weekly() {
+        let db = setup_test_db();
+        let service = StatisticsService::new(Arc::clone(&db));
+        let at = |day: u32| {
+            NaiveDate::from_ymd_opt(2024, 3, day)
+                .and_then(|d| d.and_hms_opt(12, 0, 0))
+                .unwrap()
+        };
+        let date = |day: u32| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+
+        // Outside the range on both ends
+        insert_session(&db, "1", "Alpha", at(5), 5000);
+        insert_session(&db, "2", "Beta", at(21), 5000);
+
+        insert_session(&db, "1", "Alpha", at(6), 600);
+        insert_session(&db, "1", "Alpha", at(10), 100);
+        insert_session(&db, "2", "Beta", at(10), 300);
+        insert_session(&db, "1", "Alpha", at(12), 200);
+        insert_session(&db, "2", "Beta", at(20), 400);
+
+        let weeks = service.get_weekly(date(6), date(20)).unwrap();
+        assert_eq!(weeks.len(), 3);
+
+        let totals: Vec<_> = weeks
+            .iter()
+            .map(|week| {
+                (
+                    week.start_date,
+                    week.end_date,
+                    week.games
+                        .iter()
+                        .map(|stats| (stats.game.id.as_str(), stats.time))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            totals,
+            vec![
+                (date(6), date(10), vec![("1", 700), ("2", 300)]),
+                (date(11), date(17), vec![("1", 200)]),
+                (date(18), date(20), vec![("2", 400)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_daily_totals_fills_gaps() {
+        let db = setup_test_db();
+        let service = StatisticsService::new(Arc::clone(&db));
+        let date = |day: u32| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        let at = |day: u32, hour: u32| date(day).and_hms_opt(hour, 0, 0).unwrap();
+
+        insert_session(&db, "1", "Alpha", at(1, 10), 600);
+        insert_session(&db, "2", "Beta", at(1, 20), 300);
+        insert_session(&db, "1", "Alpha", at(4, 12), 900);
+
+        assert_eq!(
+            service.get_daily_totals(date(1), date(5)).unwrap(),
+            vec![
+                (date(1), 900),
+                (date(2), 0),
+                (date(3), 0),
+                (date(4), 900),
+                (date(5), 0),
+            ]
+        );
+        assert!(
+            service
+                .get_daily_totals(date(5), date(1))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_get_sessions_per_active_day() {
+        let db = setup_test_db();
+        let service = StatisticsService::new(Arc::clone(&db));
+        let at = |day: u32, hour: u32| {
+            NaiveDate::from_ymd_opt(2024, 3, day)
+                .and_then(|d| d.and_hms_opt(hour, 0, 0))
+                .unwrap()
+        };
+
+        assert_eq!(service.get_sessions_per_active_day(None).unwrap(), None);
+
+        // Five sessions over two active days
+        for hour in [9, 13, 20] {
+            insert_session(&db, "1", "Alpha", at(1, hour), 600);
+        }
+        insert_session(&db, "1", "Alpha", at(4, 10), 600);
+        insert_session(&db, "2", "Beta", at(4, 18), 600);
+
+        assert_eq!(
+            service.get_sessions_per_active_day(None).unwrap(),
+            Some(2.5)
+        );
+        assert_eq!(
+            service.get_sessions_per_active_day(Some("2")).unwrap(),
+            Some(1.0)
+        );
+        assert_eq!(
+            service.get_sessions_per_active_day(Some("3")).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_average_session_gap() {
+        let db = setup_test_db();
+        let service = StatisticsService::new(Arc::clone(&db));
+        let at = |day: u32| {
+            NaiveDate::from_ymd_opt(2024, 3, day)
+                .and_then(|d| d.and_hms_opt(12, 0, 0))
+                .unwrap()
+        };
+
+        insert_session(&db, "1", "Alpha", at(10), 600);
+        assert_eq!(service.get_average_session_gap("1").unwrap(), None);
+
+        // Gaps of 2 and 4 days, inserted out of order
+        insert_session(&db, "1", "Alpha", at(4), 600);
+        insert_session(&db, "1", "Alpha", at(6), 600);
+
+        assert_eq!(service.get_average_session_gap("1").unwrap(), Some(3.0));
+        assert_eq!(service.get_average_session_gap("2").unwrap(), None);
+    }
+
+    #[test]
+    fn test_find_inflated_totals() {
+        let db = setup_test_db();
+        let service = StatisticsService::new(Arc::clone(&db));
+        let at = |day: u32, hour: u32| {
+            NaiveDate::from_ymd_opt(2024, 3, day)
+                .and_then(|d| d.and_hms_opt(hour, 0, 0))
+                .unwrap()
+        };
+
+        insert_session(&db, "1", "Alpha", at(1, 10), 3600);
+        insert_session(&db, "1", "Alpha", at(2, 8), 36_000);
+        insert_session(&db, "2", "Beta", at(1, 12), 6 * 3600);
+        insert_session(&db, "2", "Beta", at(3, 12), 1800);
+
+        let flagged = service.find_inflated_totals(6 * 3600).unwrap();
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0].0.name, "Alpha");
+        assert_eq!(flagged[0].1.duration, 36_000.0);
+
+        assert_eq!(service.find_inflated_totals(3600).unwrap().len(), 2);
+        assert!(service.find_inflated_totals(0).is_err());
+    }
 }
@@ -1,22 +1,81 @@
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
 
-use crate::db::{Database, TimeTrackingDao};
-use crate::error::Result;
-use crate::models::PlaySession;
+use chrono::{Duration, FixedOffset, Local, NaiveDate, NaiveDateTime};
+
+use crate::db::{Database, TimeTrackingDao, WalCheckpoint};
+use crate::error::{Error, Result};
+use crate::models::{DayAttribution, Game, ImportSession, PlaySession, TimeEntry};
+
+/// Callback invoked for every session fragment stored by `add_time`
+pub type SessionRecordedCallback = Arc<dyn Fn(&PlaySession) + Send + Sync>;
 
 #[derive(Clone)]
 pub struct TimeTrackingService {
+    db: Arc<Database>,
     dao: TimeTrackingDao,
+    recorded_since_checkpoint: Arc<AtomicU32>,
+    on_session_recorded: Option<SessionRecordedCallback>,
+    synthesized_session_day: Option<NaiveDate>,
 }
 
 impl TimeTrackingService {
     pub fn new(db: Arc<Database>) -> Self {
         Self {
-            dao: TimeTrackingDao::new(db),
+            dao: TimeTrackingDao::new(Arc::clone(&db)),
+            db,
+            recorded_since_checkpoint: Arc::new(AtomicU32::new(0)),
+            on_session_recorded: None,
+            synthesized_session_day: None,
         }
     }
 
+    /// Choose which day a session crossing a single midnight is recorded
+    /// under
+    ///
+    /// Sessions spanning more than one midnight are always split per day.
+    pub fn with_day_attribution(mut self, attribution: DayAttribution) -> Self {
+        self.dao = self.dao.with_day_attribution(attribution);
+
+        self
+    }
+
+    /// Split sessions at midnight in a fixed UTC offset instead of the
+    /// system's local timezone
+    pub fn with_utc_offset(mut self, offset: FixedOffset) -> Self {
+        self.dao = self.dao.with_utc_offset(offset);
+
+        self
+    }
+
+    /// Reject sessions spanning more than `days` days, 366 by default
+    pub fn with_max_span_days(mut self, days: u32) -> Self {
+        self.dao = self.dao.with_max_span_days(days);
+
+        self
+    }
+
+    /// Date sessions recovered by `synthesize_sessions_from_overall` on
+    /// `day` instead of today
+    pub fn with_synthesized_session_day(mut self, day: NaiveDate) -> Self {
+        self.synthesized_session_day = Some(day);
+
+        self
+    }
+
+    /// Register a callback fired once per stored session fragment after
+    /// `add_time` commits
+    ///
+    /// The callback runs after the database lock is released.
+    pub fn set_on_session_recorded(&mut self, callback: SessionRecordedCallback) {
+        self.on_session_recorded = Some(callback);
+    }
+
     /// Add playtime for a game
+    ///
+    /// When `idempotency_key` is given, retrying with the same key is a no-op
+    /// that returns the first call's result. Returns the number of rows
+    /// stored, more than one when the session was split across days.
     pub fn add_time(
         &self,
         game_id: &str,
@@ -24,9 +83,121 @@ impl TimeTrackingService {
         started_at: f64,
         ended_at: f64,
         source: Option<&str>,
+        idempotency_key: Option<&str>,
+    ) -> Result<usize> {
+        let (rows, recorded) = self.dao.add_time_with_key(
+            game_id,
+            game_name,
+            started_at,
+            ended_at,
+            source,
+            idempotency_key,
+        )?;
+
+        self.notify_recorded(&recorded);
+
+        Ok(rows)
+    }
+
+    /// Add playtime for a game unless a session with `checksum` was already
+    /// recorded for it
+    ///
+    /// Returns the number of rows stored, zero for a repeated session.
+    pub fn add_time_with_checksum(
+        &self,
+        game_id: &str,
+        game_name: &str,
+        started_at: f64,
+        ended_at: f64,
+        source: Option<&str>,
+        checksum: &str,
+    ) -> Result<usize> {
+        let recorded = self
+            .dao
+            .add_time_with_checksum(game_id, game_name, started_at, ended_at, source, checksum)?;
+
+        self.notify_recorded(&recorded);
+
+        Ok(recorded.len())
+    }
+
+    /// Add playtime for a game, excluding idle time from the recorded total
+    pub fn add_time_with_idle(
+        &self,
+        game_id: &str,
+        game_name: &str,
+        started_at: f64,
+        ended_at: f64,
+        idle_seconds: f64,
+        source: Option<&str>,
     ) -> Result<()> {
-        self.dao
-            .add_time(game_id, game_name, started_at, ended_at, source)
+        let recorded = self.dao.add_time_with_idle(
+            game_id,
+            game_name,
+            started_at,
+            ended_at,
+            idle_seconds,
+            source,
+        )?;
+
+        self.notify_recorded(&recorded);
+
+        Ok(())
+    }
+
+    /// Add many play spans in one transaction, all or nothing
+    ///
+    /// With `defer_totals`, the batch's game totals are rebuilt once at the
+    /// end instead of per game. Returns the number of rows stored.
+    pub fn add_time_batch(&self, entries: &[TimeEntry], defer_totals: bool) -> Result<usize> {
+        let recorded = self.dao.record_time_batch(entries, defer_totals)?;
+
+        self.notify_recorded(&recorded);
+
+        Ok(recorded.len())
+    }
+
+    fn notify_recorded(&self, recorded: &[PlaySession]) {
+        if let Some(callback) = &self.on_session_recorded {
+            for session in recorded {
+                callback(session);
+            }
+        }
+    }
+
+    /// Add playtime for a game and run a passive WAL checkpoint every
+    /// `checkpoint_every` recordings
+    ///
+    /// Returns the checkpoint result when one was performed by this call.
+    pub fn add_time_and_checkpoint(
+        &self,
+        game_id: &str,
+        game_name: &str,
+        started_at: f64,
+        ended_at: f64,
+        source: Option<&str>,
+        checkpoint_every: u32,
+    ) -> Result<Option<WalCheckpoint>> {
+        if checkpoint_every == 0 {
+            return Err(Error::InvalidInput(
+                "checkpoint_every must be greater than zero".into(),
+            ));
+        }
+
+        self.add_time(game_id, game_name, started_at, ended_at, source, None)?;
+
+        let recorded = self
+            .recorded_since_checkpoint
+            .fetch_add(1, Ordering::SeqCst)
+            + 1;
+
+        if recorded < checkpoint_every {
+            return Ok(None);
+        }
+
+        self.recorded_since_checkpoint.store(0, Ordering::SeqCst);
+
+        self.db.checkpoint_passive().map(Some)
     }
 
     /// Apply manual time correction
@@ -41,13 +212,424 @@ impl TimeTrackingService {
             .apply_manual_time_correction(game_id, game_name, time_seconds, source)
     }
 
+    /// Record playtime known only as a duration on a given date
+    pub fn add_duration(
+        &self,
+        game_id: &str,
+        game_name: &str,
+        duration_secs: i64,
+        date: NaiveDateTime,
+        source: &str,
+    ) -> Result<()> {
+        self.dao
+            .add_duration(game_id, game_name, duration_secs, date, source)
+    }
+
+    /// Restore sessions from a backup, skipping ones already recorded
+    ///
+    /// Returns the number of sessions inserted.
+    pub fn import_sessions(&self, sessions: &[ImportSession]) -> Result<usize> {
+        self.dao.import_sessions(sessions)
+    }
+
     /// Get all sessions for a game
     pub fn get_game_sessions(&self, game_id: &str) -> Result<Vec<PlaySession>> {
         self.dao.get_game_sessions(game_id)
     }
 
+    /// Get a game's sessions dated within `start..=end`, newest first
+    pub fn get_game_sessions_between(
+        &self,
+        game_id: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<PlaySession>> {
+        self.dao.get_game_sessions_between(game_id, start, end)
+    }
+
+    /// Get the fragments created by splitting sessions at midnight
+    pub fn get_synthetic_sessions(&self, game_id: &str) -> Result<Vec<PlaySession>> {
+        self.dao.get_synthetic_sessions(game_id)
+    }
+
+    /// Get the newest sessions across all games with their names
+    pub fn get_recent_sessions_with_names(&self, limit: i64) -> Result<Vec<(Game, PlaySession)>> {
+        self.dao.get_recent_sessions_with_names(limit)
+    }
+
+    /// Get one page of a game's sessions, newest first
+    pub fn get_game_sessions_paged(
+        &self,
+        game_id: &str,
+        offset: i64,
+        limit: i64,
+        min_duration: Option<i64>,
+    ) -> Result<Vec<PlaySession>> {
+        self.dao
+            .get_game_sessions_paged(game_id, offset, limit, min_duration)
+    }
+
+    /// Count a game's sessions for paging
+    pub fn count_game_sessions(&self, game_id: &str, min_duration: Option<i64>) -> Result<i64> {
+        self.dao.count_game_sessions(game_id, min_duration)
+    }
+
     /// Get total playtime for a game
     pub fn get_total_playtime(&self, game_id: &str) -> Result<i64> {
         self.dao.get_total_playtime(game_id)
     }
+
+    /// Get total playtime for a game as a [`Duration`]
+    pub fn get_total_playtime_duration(&self, game_id: &str) -> Result<Duration> {
+        self.get_total_playtime(game_id).map(Duration::seconds)
+    }
+
+    /// Collapse exact-duplicate session rows and rebuild the cached totals
+    ///
+    /// Returns the number of rows removed.
+    pub fn deduplicate_sessions(&self, game_id: Option<&str>) -> Result<usize> {
+        self.dao.deduplicate_sessions(game_id)
+    }
+
+    /// Keep only a game's newest `max_sessions` sessions, leaving its total
+    /// playtime unchanged
+    ///
+    /// Returns the number of sessions removed.
+    pub fn enforce_session_cap(&self, game_id: &str, max_sessions: usize) -> Result<usize> {
+        self.dao.enforce_session_cap(game_id, max_sessions)
+    }
+
+    /// Merge each day's sessions into one daily total for days older than
+    /// `detail_days`, keeping recent days in full detail
+    ///
+    /// Returns the number of rows removed.
+    pub fn compact_old_sessions(&self, game_id: &str, detail_days: i64) -> Result<usize> {
+        if detail_days < 0 {
+            return Err(Error::InvalidInput(
+                "Detail days must not be negative".into(),
+            ));
+        }
+
+        let cutoff = Local::now().date_naive() - Duration::days(detail_days);
+
+        self.dao.compact_sessions_before(game_id, cutoff)
+    }
+
+    /// Recompute one game's cached total from its sessions
+    pub fn rebuild_overall_time_for_game(&self, game_id: &str) -> Result<i64> {
+        self.dao.rebuild_overall_time_for_game(game_id)
+    }
+
+    /// Recompute every played game's cached total from its sessions
+    pub fn rebuild_overall_time(&self) -> Result<usize> {
+        self.dao.rebuild_overall_time()
+    }
+
+    /// Recover a single synthetic session for every game that has a total in
+    /// `overall_time` but no session rows
+    ///
+    /// Returns the number of sessions created.
+    pub fn synthesize_sessions_from_overall(&self) -> Result<usize> {
+        let day = self
+            .synthesized_session_day
+            .unwrap_or_else(|| Local::now().date_naive());
+
+        self.dao.synthesize_sessions_from_overall(day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use std::sync::atomic::AtomicUsize;
+
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::db::migrations::run_migrations;
+
+    fn setup_test_service() -> TimeTrackingService {
+        let db_path = env::temp_dir().join(format!("test_service_{}.db", uuid::Uuid::new_v4()));
+        let db = Database::new(&db_path).unwrap();
+        db.with_connection(run_migrations).unwrap();
+
+        TimeTrackingService::new(Arc::new(db))
+    }
+
+    #[test]
+    fn test_add_time_and_checkpoint() {
+        let service = setup_test_service();
+        let now = Local::now().timestamp() as f64;
+
+        for i in 0..2 {
+            let start = now + f64::from(i) * 100.0;
+            let checkpoint = service
+                .add_time_and_checkpoint("123", "Test Game", start, start + 60.0, None, 3)
+                .unwrap();
+            assert!(checkpoint.is_none());
+        }
+
+        let checkpoint = service
+            .add_time_and_checkpoint("123", "Test Game", now + 200.0, now + 260.0, None, 3)
+            .unwrap()
+            .expect("third recording should checkpoint");
+
+        assert!(checkpoint.log_frames > 0);
+        assert_eq!(checkpoint.checkpointed_frames, checkpoint.log_frames);
+
+        let checkpoint = service
+            .add_time_and_checkpoint("123", "Test Game", now + 300.0, now + 360.0, None, 3)
+            .unwrap();
+        assert!(checkpoint.is_none());
+    }
+
+    #[test]
+    fn test_total_playtime_duration() {
+        let service = setup_test_service();
+        let noon = Local
+            .with_ymd_and_hms(2024, 3, 1, 12, 0, 0)
+            .unwrap()
+            .timestamp() as f64;
+
+        assert_eq!(
+            service.get_total_playtime_duration("123").unwrap(),
+            Duration::zero()
+        );
+
+        service
+            .add_time("123", "Test Game", noon, noon + 5400.0, None, None)
+            .unwrap();
+
+        let total = service.get_total_playtime_duration("123").unwrap();
+        assert_eq!(
+            total.num_seconds(),
+            service.get_total_playtime("123").unwrap()
+        );
+        assert_eq!(total, Duration::minutes(90));
+    }
+
+    #[test]
+    fn test_on_session_recorded_fires_per_fragment() {
+        let mut service = setup_test_service();
+        let fired = Arc::new(AtomicUsize::new(0));
+
+        let counter = Arc::clone(&fired);
+        service.set_on_session_recorded(Arc::new(move |session| {
+            assert_eq!(session.game_id, "123");
+            counter.fetch_add(1, Ordering::SeqCst);
+        }));
+
+        let late = Local
+            .with_ymd_and_hms(2024, 3, 1, 22, 0, 0)
+            .unwrap()
+            .timestamp() as f64;
+
+        service
+            .add_time("123", "Test Game", late - 7200.0, late - 3600.0, None, None)
+            .unwrap();
+        assert_eq!(fired.load(Ordering::SeqCst), 1);
+
+        // 22:00 -> 02:00 is split into two fragments
+        service
+            .add_time("123", "Test Game", late, late + 4.0 * 3600.0, None, None)
+            .unwrap();
+        assert_eq!(fired.load(Ordering::SeqCst), 3);
+
+        assert!(
+            service
+                .add_time("123", "Test Game", late, late, None, None)
+                .is_err()
+        );
+        assert_eq!(fired.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_add_duration() {
+        let service = setup_test_service();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1)
+            .and_then(|d| d.and_hms_opt(18, 0, 0))
+            .unwrap();
+
+        service
+            .add_duration("123", "Test Game", 5400, date, "storefront")
+            .unwrap();
+
+        let sessions = service.get_game_sessions("123").unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].duration, 5400.0);
+        assert_eq!(sessions[0].started_date(), date);
+        assert_eq!(service.get_total_playtime("123").unwrap(), 5400);
+
+        let overall: i64 = service
+            .db
+            .with_connection(|conn| {
+                Ok(conn.query_row(
+                    "SELECT duration FROM overall_time WHERE game_id = '123'",
+                    [],
+                    |row| row.get(0),
+                )?)
+            })
+            .unwrap();
+        assert_eq!(overall, 5400);
+
+        assert!(
+            service
+                .add_duration("123", "Test Game", 0, date, "storefront")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_end_day_attribution() {
+        let late_night = Local
+            .with_ymd_and_hms(2024, 3, 1, 23, 0, 0)
+            .unwrap()
+            .timestamp() as f64;
+        let end_day = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+
+        let service = setup_test_service().with_day_attribution(DayAttribution::EndDay);
+        service
+            .add_time(
+                "123",
+                "Test Game",
+                late_night,
+                late_night + 7200.0,
+                None,
+                None,
+            )
+            .unwrap();
+
+        let sessions = service.get_game_sessions("123").unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(
+            sessions[0].started_date(),
+            end_day.and_hms_opt(1, 0, 0).unwrap()
+        );
+        assert_eq!(sessions[0].duration, 7200.0);
+
+        let service = setup_test_service();
+        service
+            .add_time(
+                "123",
+                "Test Game",
+                late_night,
+                late_night + 7200.0,
+                None,
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(service.get_game_sessions("123").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_add_time_with_idempotency_key() {
+        let service = setup_test_service();
+        // Crosses midnight so the first call stores two rows
+        let late = Local
+            .with_ymd_and_hms(2024, 3, 1, 23, 30, 0)
+            .unwrap()
+            .timestamp() as f64;
+
+        let first = service
+            .add_time("123", "Test Game", late, late + 3600.0, None, Some("abc"))
+            .unwrap();
+        let retry = service
+            .add_time("123", "Test Game", late, late + 3600.0, None, Some("abc"))
+            .unwrap();
+
+        assert_eq!(first, 2);
+        assert_eq!(retry, first);
+        assert_eq!(service.get_game_sessions("123").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_paging_through_sessions() {
+        let service = setup_test_service();
+        let day_one = Local.with_ymd_and_hms(2024, 3, 1, 10, 0, 0).unwrap();
+
+        for day in 0..25 {
+            let started_at = (day_one + Duration::days(day)).timestamp() as f64;
+            service
+                .add_time(
+                    "123",
+                    "Test Game",
+                    started_at,
+                    started_at + 600.0,
+                    None,
+                    None,
+                )
+                .unwrap();
+        }
+
+        assert_eq!(service.count_game_sessions("123", None).unwrap(), 25);
+
+        let pages: Vec<Vec<PlaySession>> = (0..3)
+            .map(|page| {
+                service
+                    .get_game_sessions_paged("123", page * 10, 10, None)
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(
+            pages.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![10, 10, 5]
+        );
+
+        // Newest first, continuing across page boundaries
+        let starts: Vec<f64> = pages.iter().flatten().map(|s| s.started_at).collect();
+        assert!(starts.windows(2).all(|pair| pair[0] > pair[1]));
+        assert_eq!(starts[0], (day_one + Duration::days(24)).timestamp() as f64);
+        assert_eq!(starts[24], day_one.timestamp() as f64);
+
+        for (offset, limit) in [(-10, 10), (0, -10), (0, 0)] {
+            let err = service
+                .get_game_sessions_paged("123", offset, limit, None)
+                .unwrap_err();
+            assert!(matches!(err, Error::InvalidInput(_)));
+        }
+    }
+
+    #[test]
+    fn test_compact_old_sessions() {
+        let service = setup_test_service();
+        let today = Local::now().date_naive();
+        let add = |days_ago: u64, hour: u32, minutes: i64| {
+            let start = (today - chrono::Days::new(days_ago))
+                .and_hms_opt(hour, 0, 0)
+                .and_then(|dt| Local.from_local_datetime(&dt).single())
+                .unwrap()
+                .timestamp() as f64;
+            service
+                .add_time(
+                    "123",
+                    "Test Game",
+                    start,
+                    start + minutes as f64 * 60.0,
+                    None,
+                    None,
+                )
+                .unwrap();
+        };
+
+        for hour in [9, 13, 20] {
+            add(40, hour, 30);
+        }
+        add(35, 10, 60);
+        add(35, 18, 15);
+        add(31, 10, 45);
+        add(2, 10, 20);
+        add(2, 15, 40);
+
+        assert_eq!(service.compact_old_sessions("123", 30).unwrap(), 3);
+        assert_eq!(service.compact_old_sessions("123", 30).unwrap(), 0);
+
+        let sessions = service.get_game_sessions("123").unwrap();
+        let durations: Vec<f64> = sessions.iter().map(|s| s.duration).collect();
+        assert_eq!(durations, vec![2400.0, 1200.0, 2700.0, 4500.0, 5400.0]);
+        assert_eq!(service.get_total_playtime("123").unwrap(), 16_200);
+
+        assert!(service.compact_old_sessions("123", -1).is_err());
+    }
 }
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::db::migrations::run_migrations;
+use crate::db::{Database, StatisticsDao};
+use crate::error::{Error, Result};
+use crate::models::GameStatistics;
+
+const USERS_SUBDIR: &str = "users";
+const STORAGE_DB_FILENAME: &str = "storage.db";
+
+/// Per-user databases stored under `<data_dir>/users/<user_id>/storage.db`
+#[derive(Debug, Clone)]
+pub struct UserStore {
+    data_dir: PathBuf,
+}
+
+impl UserStore {
+    pub fn new<P: AsRef<Path>>(data_dir: P) -> Self {
+        Self {
+            data_dir: data_dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Path of a user's database file
+    pub fn user_db_path(&self, user_id: &str) -> PathBuf {
+        self.data_dir
+            .join(USERS_SUBDIR)
+            .join(user_id)
+            .join(STORAGE_DB_FILENAME)
+    }
+
+    /// Open an existing user database and bring its schema up to date
+    pub fn open_user(&self, user_id: &str) -> Result<Database> {
+        let db_path = self.user_db_path(user_id);
+
+        if !db_path.is_file() {
+            return Err(Error::NotFound(format!("No database for user {}", user_id)));
+        }
+
+        let db = Database::new(&db_path)?;
+        db.with_connection(run_migrations)?;
+
+        Ok(db)
+    }
+
+    /// Combine per-game totals from several users into one leaderboard
+    ///
+    /// Games are matched by `game_id`; the name comes from whichever user
+    /// database is read first.
+    pub fn aggregate_overall(&self, user_ids: &[&str]) -> Result<Vec<GameStatistics>> {
+        let mut combined: HashMap<String, GameStatistics> = HashMap::new();
+
+        for user_id in user_ids {
+            let dao = StatisticsDao::new(self.open_user(user_id)?.into());
+
+            for stats in dao.get_overall_statistics()? {
+                match combined.get_mut(&stats.game.id) {
+                    Some(existing) => {
+                        existing.total_time += stats.total_time;
+                        existing.total_sessions += stats.total_sessions;
+                        existing.last_played = existing.last_played.max(stats.last_played);
+                    }
+                    None => {
+                        combined.insert(stats.game.id.clone(), stats);
+                    }
+                }
+            }
+        }
+
+        let mut result: Vec<GameStatistics> = combined.into_values().collect();
+        result.sort_by(|a, b| {
+            b.total_time
+                .cmp(&a.total_time)
+                .then_with(|| a.game.name.cmp(&b.game.name))
+        });
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use rusqlite::params;
+
+    use super::*;
+
+    fn create_user(store: &UserStore, user_id: &str, sessions: &[(&str, &str, &str, i64)]) {
+        let db_path = store.user_db_path(user_id);
+        let db = Database::new(&db_path).unwrap();
+        db.with_connection(run_migrations).unwrap();
+
+        db.with_connection(|conn| {
+            for (game_id, name, date, time) in sessions {
+                conn.execute(
+                    "INSERT OR IGNORE INTO game_dict (game_id, name) VALUES (?1, ?2)",
+                    params![game_id, name],
+                )?;
+                conn.execute(
+                    "INSERT INTO play_time (game_id, date_time, duration) VALUES (?1, ?2, ?3)",
+                    params![game_id, date, time],
+                )?;
+            }
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_aggregate_overall() {
+        let data_dir = env::temp_dir().join(format!("test_users_{}", uuid::Uuid::new_v4()));
+        let store = UserStore::new(&data_dir);
+
+        create_user(
+            &store,
+            "1001",
+            &[
+                ("1", "Shared", "2024-01-01T10:00:00", 600),
+                ("2", "Solo", "2024-01-02T10:00:00", 900),
+            ],
+        );
+        create_user(
+            &store,
+            "1002",
+            &[
+                ("1", "Shared", "2024-01-05T10:00:00", 1200),
+                ("1", "Shared", "2024-01-06T10:00:00", 300),
+            ],
+        );
+
+        let combined = store.aggregate_overall(&["1001", "1002"]).unwrap();
+
+        assert_eq!(combined.len(), 2);
+        assert_eq!(combined[0].game.id, "1");
+        assert_eq!(combined[0].total_time, 2100);
+        assert_eq!(combined[0].total_sessions, 3);
+        assert_eq!(
+            combined[0].last_played.unwrap().to_string(),
+            "2024-01-06 10:00:00"
+        );
+        assert_eq!(combined[1].game.id, "2");
+        assert_eq!(combined[1].total_time, 900);
+
+        assert!(
+            store
+                .aggregate_overall(&["1001", "9999"])
+                .unwrap_err()
+                .is_not_found()
+        );
+
+        std::fs::remove_dir_all(data_dir).ok();
+    }
+}
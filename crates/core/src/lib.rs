@@ -1,3 +1,5 @@
+#[cfg(feature = "async")]
+pub mod r#async;
 pub mod db;
 pub mod domain;
 pub mod error;
@@ -23,17 +23,151 @@ pub struct GameChecksum {
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+/// Stored checksum details without the digest
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChecksumMeta {
+    pub algorithm: ChecksumAlgorithm,
+    pub chunk_size: usize,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Hash algorithms accepted by the `game_file_checksum.algorithm` column
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ChecksumAlgorithm {
+    Blake2b,
+    Blake2s,
+    Sha224,
     Sha256,
-    Md5,
+    Sha384,
+    Sha512,
+    Sha512_224,
+    Sha512_256,
+    Sha3_224,
+    Sha3_256,
+    Sha3_384,
+    Sha3_512,
+    Shake128,
+    Shake256,
+}
+
+impl ChecksumAlgorithm {
+    pub const ALL: [Self; 14] = [
+        Self::Blake2b,
+        Self::Blake2s,
+        Self::Sha224,
+        Self::Sha256,
+        Self::Sha384,
+        Self::Sha512,
+        Self::Sha512_224,
+        Self::Sha512_256,
+        Self::Sha3_224,
+        Self::Sha3_256,
+        Self::Sha3_384,
+        Self::Sha3_512,
+        Self::Shake128,
+        Self::Shake256,
+    ];
+
+    /// Token stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Blake2b => "BLAKE2B",
+            Self::Blake2s => "BLAKE2S",
+            Self::Sha224 => "SHA224",
+            Self::Sha256 => "SHA256",
+            Self::Sha384 => "SHA384",
+            Self::Sha512 => "SHA512",
+            Self::Sha512_224 => "SHA512_224",
+            Self::Sha512_256 => "SHA512_256",
+            Self::Sha3_224 => "SHA3_224",
+            Self::Sha3_256 => "SHA3_256",
+            Self::Sha3_384 => "SHA3_384",
+            Self::Sha3_512 => "SHA3_512",
+            Self::Shake128 => "SHAKE_128",
+            Self::Shake256 => "SHAKE_256",
+        }
+    }
 }
 
 impl std::fmt::Display for ChecksumAlgorithm {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Sha256 => write!(f, "sha256"),
-            Self::Md5 => write!(f, "md5"),
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ChecksumAlgorithm {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.as_str() == s)
+            .ok_or_else(|| {
+                crate::error::Error::InvalidInput(format!("Unknown checksum algorithm: {}", s))
+            })
+    }
+}
+
+/// Prefixes that are directly followed by an app id in Steam URIs and URLs
+const APP_ID_MARKERS: &[&str] = &[
+    "steam://rungameid/",
+    "steam://run/",
+    "steam://nav/games/details/",
+    "/app/",
+];
+
+/// Extract a Steam app id from a bare id, a `steam://` URI or a store or
+/// community URL
+pub fn parse_app_id_from_input(input: &str) -> Option<String> {
+    let input = input.trim();
+
+    if !input.is_empty() && input.bytes().all(|b| b.is_ascii_digit()) {
+        return Some(input.to_string());
+    }
+
+    APP_ID_MARKERS.iter().find_map(|marker| {
+        let (_, rest) = input.split_once(marker)?;
+        let id: String = rest.chars().take_while(char::is_ascii_digit).collect();
+
+        (!id.is_empty()).then_some(id)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum_algorithm_round_trip() {
+        for algorithm in ChecksumAlgorithm::ALL {
+            assert_eq!(
+                algorithm.to_string().parse::<ChecksumAlgorithm>().unwrap(),
+                algorithm
+            );
         }
+
+        assert!("MD5".parse::<ChecksumAlgorithm>().is_err());
+        assert!("sha256".parse::<ChecksumAlgorithm>().is_err());
+    }
+
+    #[test]
+    fn test_parse_app_id_from_input() {
+        assert_eq!(
+            parse_app_id_from_input("steam://rungameid/440").as_deref(),
+            Some("440")
+        );
+        assert_eq!(
+            parse_app_id_from_input("https://store.steampowered.com/app/1245620/ELDEN_RING/")
+                .as_deref(),
+            Some("1245620")
+        );
+        assert_eq!(parse_app_id_from_input(" 730 ").as_deref(), Some("730"));
+
+        assert_eq!(
+            parse_app_id_from_input("https://store.steampowered.com/"),
+            None
+        );
+        assert_eq!(parse_app_id_from_input("Team Fortress 2"), None);
+        assert_eq!(parse_app_id_from_input(""), None);
     }
 }
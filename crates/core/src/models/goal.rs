@@ -0,0 +1,8 @@
+/// Progress towards a playtime budget over one period, in seconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GoalProgress {
+    pub goal_seconds: i64,
+    pub played_seconds: i64,
+    /// Seconds left before the goal is met, never negative
+    pub remaining: i64,
+}
@@ -1,7 +1,12 @@
 pub mod game;
+pub mod goal;
 pub mod session;
 pub mod statistics;
 
-pub use game::{ChecksumAlgorithm, Game, GameChecksum};
-pub use session::PlaySession;
-pub use statistics::{DailyGameStats, DailyStatistics, GameStatistics, SessionInfo};
+pub use game::{ChecksumAlgorithm, ChecksumMeta, Game, GameChecksum};
+pub use goal::GoalProgress;
+pub use session::{DayAttribution, ImportSession, PlaySession, TimeEntry};
+pub use statistics::{
+    AdjustmentSummary, DailyGameStats, DailyStatistics, GameStatistics, LibraryTotals, SessionInfo,
+    StatisticsSnapshot, StreakInfo, WeeklyGameStats, WeeklyStatistics,
+};
@@ -1,5 +1,35 @@
 use chrono::{Local, NaiveDateTime, TimeZone};
 
+/// Which calendar day a session crossing midnight is recorded under
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DayAttribution {
+    /// Record under the day the session started
+    #[default]
+    StartDay,
+    /// Record under the day the session ended
+    EndDay,
+}
+
+/// A session restored from a backup, already attributed to a date
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportSession {
+    pub game_id: String,
+    pub game_name: String,
+    pub date_time: NaiveDateTime,
+    pub duration: i64,
+    pub source: Option<String>,
+}
+
+/// A play span to record as part of a batch
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeEntry {
+    pub game_id: String,
+    pub game_name: String,
+    pub started_at: f64,
+    pub ended_at: f64,
+    pub source: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct PlaySession {
     pub game_id: String,
@@ -28,29 +58,38 @@ impl PlaySession {
     }
 
     pub fn started_date(&self) -> NaiveDateTime {
-        let secs = self.started_at.trunc() as i64;
-        let nanos = ((self.started_at.fract() * 1_000_000_000.0) as u32).min(999_999_999);
-        Local
-            .timestamp_opt(secs, nanos)
-            .single()
-            .map(|dt| dt.naive_local())
-            .unwrap_or_else(|| Local::now().naive_local())
+        self.started_date_in(&Local)
     }
 
     pub fn ended_date(&self) -> NaiveDateTime {
-        let secs = self.ended_at.trunc() as i64;
-        let nanos = ((self.ended_at.fract() * 1_000_000_000.0) as u32).min(999_999_999);
-        Local
-            .timestamp_opt(secs, nanos)
-            .single()
-            .map(|dt| dt.naive_local())
-            .unwrap_or_else(|| Local::now().naive_local())
+        self.ended_date_in(&Local)
     }
 
     pub fn is_multi_day(&self) -> bool {
-        let start_date = self.started_date().date();
-        let end_date = self.ended_date().date();
+        self.is_multi_day_in(&Local)
+    }
+
+    /// Wall-clock start time in `tz`
+    pub fn started_date_in<Tz: TimeZone>(&self, tz: &Tz) -> NaiveDateTime {
+        wall_clock_in(tz, self.started_at)
+    }
 
-        start_date != end_date
+    /// Wall-clock end time in `tz`
+    pub fn ended_date_in<Tz: TimeZone>(&self, tz: &Tz) -> NaiveDateTime {
+        wall_clock_in(tz, self.ended_at)
     }
+
+    /// Whether the session crosses midnight in `tz`
+    pub fn is_multi_day_in<Tz: TimeZone>(&self, tz: &Tz) -> bool {
+        self.started_date_in(tz).date() != self.ended_date_in(tz).date()
+    }
+}
+
+fn wall_clock_in<Tz: TimeZone>(tz: &Tz, timestamp: f64) -> NaiveDateTime {
+    let secs = timestamp.trunc() as i64;
+    let nanos = ((timestamp.fract() * 1_000_000_000.0) as u32).min(999_999_999);
+    tz.timestamp_opt(secs, nanos)
+        .single()
+        .map(|dt| dt.naive_local())
+        .unwrap_or_else(|| Local::now().naive_local())
 }
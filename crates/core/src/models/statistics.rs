@@ -1,4 +1,4 @@
-use chrono::{NaiveDate, NaiveDateTime};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
 
 use crate::models::Game;
 
@@ -11,10 +11,18 @@ pub struct GameStatistics {
     pub last_session_duration: Option<i64>,
 }
 
+impl GameStatistics {
+    /// Total playtime as a [`Duration`]
+    pub fn total_duration(&self) -> Duration {
+        Duration::seconds(self.total_time)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct DailyStatistics {
     pub date: NaiveDate,
     pub games: Vec<DailyGameStats>,
+    pub note: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -24,6 +32,56 @@ pub struct DailyGameStats {
     pub sessions: Vec<SessionInfo>,
 }
 
+/// Per-game totals for one calendar week (Monday to Sunday), clipped to the
+/// queried range
+#[derive(Debug, Clone)]
+pub struct WeeklyStatistics {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub games: Vec<WeeklyGameStats>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WeeklyGameStats {
+    pub game: Game,
+    pub time: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct StatisticsSnapshot {
+    pub taken_at: NaiveDateTime,
+    pub total_time: i64,
+    pub game_count: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdjustmentSummary {
+    pub adjusted_time: i64,
+    pub raw_time: i64,
+}
+
+impl AdjustmentSummary {
+    /// Seconds removed from the wall-clock span by adjustments
+    pub fn trimmed(&self) -> i64 {
+        self.raw_time - self.adjusted_time
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LibraryTotals {
+    pub total_secs: i64,
+    pub total_games: i64,
+    pub total_sessions: i64,
+    pub games_with_playtime: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreakInfo {
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    pub last_played: Option<NaiveDate>,
+}
+
 #[derive(Debug, Clone)]
 pub struct SessionInfo {
     pub date: NaiveDateTime,
@@ -41,4 +99,30 @@ impl SessionInfo {
             checksum: None,
         }
     }
+
+    /// Session length as a [`Duration`], rounded to the millisecond
+    pub fn as_duration(&self) -> Duration {
+        Duration::milliseconds((self.duration * 1000.0).round() as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duration_conversions() {
+        let stats = GameStatistics {
+            game: Game::new("123", "Test Game"),
+            total_time: 5400,
+            total_sessions: 2,
+            last_played: None,
+            last_session_duration: None,
+        };
+        assert_eq!(stats.total_duration().num_seconds(), 5400);
+
+        let session = SessionInfo::new(NaiveDateTime::default(), 90.25);
+        assert_eq!(session.as_duration().num_seconds(), 90);
+        assert_eq!(session.as_duration().num_milliseconds(), 90_250);
+    }
 }
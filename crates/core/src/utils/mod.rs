@@ -1,3 +1,6 @@
 pub mod time;
 
-pub use time::{end_of_day, split_session_by_day, start_of_day};
+pub use time::{
+    DurationStyle, end_of_day, format_duration, split_session_by_day, split_session_by_day_in,
+    start_of_day, start_of_week,
+};
@@ -1,7 +1,64 @@
-use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use std::str::FromStr;
 
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, TimeZone};
+
+use crate::error::Error;
 use crate::models::PlaySession;
 
+/// Output style for [`format_duration`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DurationStyle {
+    /// `2h 30m`
+    HoursMinutes,
+    /// `02:30:00`
+    Clock,
+    /// `2h30m`, dropping zero components
+    Compact,
+}
+
+impl FromStr for DurationStyle {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hm" => Ok(Self::HoursMinutes),
+            "clock" => Ok(Self::Clock),
+            "compact" => Ok(Self::Compact),
+            _ => Err(Error::InvalidInput(format!(
+                "Unknown duration style: {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// Format a number of seconds for display
+pub fn format_duration(seconds: i64, style: DurationStyle) -> String {
+    let sign = if seconds < 0 { "-" } else { "" };
+    let total = seconds.unsigned_abs();
+    let (hours, minutes, secs) = (total / 3600, total % 3600 / 60, total % 60);
+
+    match style {
+        DurationStyle::HoursMinutes => format!("{}{}h {}m", sign, hours, minutes),
+        DurationStyle::Clock => format!("{}{:02}:{:02}:{:02}", sign, hours, minutes, secs),
+        DurationStyle::Compact => {
+            let mut out = String::from(sign);
+
+            if hours > 0 {
+                out.push_str(&format!("{}h", hours));
+            }
+            if minutes > 0 {
+                out.push_str(&format!("{}m", minutes));
+            }
+            if hours == 0 && (minutes == 0 || secs > 0) {
+                out.push_str(&format!("{}s", secs));
+            }
+
+            out
+        }
+    }
+}
+
 /// Get the end of day (23:59:59) for a given timestamp
 pub fn end_of_day(dt: NaiveDateTime) -> NaiveDateTime {
     NaiveDate::from_ymd_opt(dt.year(), dt.month(), dt.day())
@@ -16,13 +73,23 @@ pub fn start_of_day(dt: NaiveDateTime) -> NaiveDateTime {
         .unwrap_or(dt)
 }
 
+/// Get the Monday starting the week that contains `date`
+pub fn start_of_week(date: NaiveDate) -> NaiveDate {
+    date - chrono::Duration::days(i64::from(date.weekday().num_days_from_monday()))
+}
+
 /// Split a play session that spans multiple days into separate sessions
 /// Each session will be bounded by day boundaries
 pub fn split_session_by_day(session: &PlaySession) -> Vec<PlaySession> {
-    let start = session.started_date();
-    let end = session.ended_date();
+    split_session_by_day_in(session, &Local)
+}
+
+/// Split a play session at each midnight in `tz`
+pub fn split_session_by_day_in<Tz: TimeZone>(session: &PlaySession, tz: &Tz) -> Vec<PlaySession> {
+    let start = session.started_date_in(tz);
+    let end = session.ended_date_in(tz);
 
-    if !session.is_multi_day() {
+    if !session.is_multi_day_in(tz) {
         return vec![session.clone()];
     }
 
@@ -38,15 +105,15 @@ pub fn split_session_by_day(session: &PlaySession) -> Vec<PlaySession> {
         let day_end = end_of_day(current_start);
         let session_end = if day_end < end { day_end } else { end };
 
-        let duration = (session_end.and_utc().timestamp_millis()
-            - current_start.and_utc().timestamp_millis()) as f64
-            / 1000.0;
+        let started_at = timestamp_in(tz, current_start);
+        let ended_at = timestamp_in(tz, session_end);
+        let duration = ended_at - started_at;
 
         if duration > 0.0 {
             sessions.push(PlaySession {
                 game_id: session.game_id.clone(),
-                started_at: current_start.and_utc().timestamp_millis() as f64 / 1000.0,
-                ended_at: session_end.and_utc().timestamp_millis() as f64 / 1000.0,
+                started_at,
+                ended_at,
                 duration,
                 checksum: session.checksum.clone(),
             });
@@ -59,9 +126,19 @@ pub fn split_session_by_day(session: &PlaySession) -> Vec<PlaySession> {
     sessions
 }
 
+/// Unix timestamp in seconds of a wall-clock time in `tz`
+fn timestamp_in<Tz: TimeZone>(tz: &Tz, dt: NaiveDateTime) -> f64 {
+    let millis = tz.from_local_datetime(&dt).earliest().map_or_else(
+        || dt.and_utc().timestamp_millis(),
+        |dt| dt.timestamp_millis(),
+    );
+
+    millis as f64 / 1000.0
+}
+
 #[cfg(test)]
 mod tests {
-    use chrono::{Datelike, Timelike};
+    use chrono::{Datelike, FixedOffset, Timelike};
 
     use super::*;
 
@@ -110,6 +187,32 @@ mod tests {
         assert!(splits[1].duration > 7100.0 && splits[1].duration < 7300.0);
     }
 
+    #[test]
+    fn test_split_depends_on_offset() {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1)
+            .and_then(|d| d.and_hms_opt(22, 0, 0))
+            .unwrap()
+            .and_utc()
+            .timestamp() as f64;
+        let session = PlaySession::new("game123".to_string(), start, start + 4.0 * 3600.0);
+
+        // 22:00-02:00 in UTC, 23:00-03:00 at UTC+1
+        let utc = split_session_by_day_in(&session, &FixedOffset::east_opt(0).unwrap());
+        let plus_one = split_session_by_day_in(&session, &FixedOffset::east_opt(3600).unwrap());
+
+        assert_eq!(utc.len(), 2);
+        assert_eq!(plus_one.len(), 2);
+        assert_eq!(utc[1].started_at, start + 2.0 * 3600.0);
+        assert_eq!(plus_one[1].started_at, start + 3600.0);
+        assert_eq!(utc[1].duration, 2.0 * 3600.0);
+        assert_eq!(plus_one[1].duration, 3.0 * 3600.0);
+
+        // 01:00-05:00 at UTC+3 stays on one day
+        let plus_three =
+            split_session_by_day_in(&session, &FixedOffset::east_opt(3 * 3600).unwrap());
+        assert_eq!(plus_three.len(), 1);
+    }
+
     #[test]
     fn test_end_of_day() {
         let dt = NaiveDate::from_ymd_opt(2024, 1, 15)
@@ -135,4 +238,36 @@ mod tests {
         assert_eq!(sod.second(), 0);
         assert_eq!(sod.day(), 15);
     }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(9000, DurationStyle::HoursMinutes), "2h 30m");
+        assert_eq!(format_duration(9000, DurationStyle::Clock), "02:30:00");
+        assert_eq!(format_duration(9000, DurationStyle::Compact), "2h30m");
+        assert_eq!(format_duration(7200, DurationStyle::Compact), "2h");
+        assert_eq!(format_duration(45, DurationStyle::Compact), "45s");
+        assert_eq!(format_duration(0, DurationStyle::Compact), "0s");
+        assert_eq!(format_duration(-90, DurationStyle::Clock), "-00:01:30");
+
+        assert_eq!(
+            "hm".parse::<DurationStyle>().unwrap(),
+            DurationStyle::HoursMinutes
+        );
+        assert!("days".parse::<DurationStyle>().is_err());
+    }
+
+    #[test]
+    fn test_start_of_week() {
+        let monday = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+
+        assert_eq!(start_of_week(monday), monday);
+        assert_eq!(
+            start_of_week(NaiveDate::from_ymd_opt(2024, 1, 7).unwrap()),
+            monday
+        );
+        assert_eq!(
+            start_of_week(NaiveDate::from_ymd_opt(2024, 1, 8).unwrap()),
+            NaiveDate::from_ymd_opt(2024, 1, 8).unwrap()
+        );
+    }
 }
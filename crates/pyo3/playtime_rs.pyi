@@ -2,12 +2,108 @@
 # ruff: noqa: E501, F401
 
 import builtins
+import datetime
 import typing
 
+@typing.final
+class DailyGameStats:
+    r"""
+    Playtime for one game on one day
+    """
+    @property
+    def game_id(self) -> builtins.str: ...
+    @property
+    def game_name(self) -> builtins.str: ...
+    @property
+    def time(self) -> builtins.int: ...
+    @property
+    def sessions(self) -> builtins.list[SessionInfo]: ...
+
+@typing.final
+class DailyStatistics:
+    r"""
+    Per-game playtime for one day
+    """
+    @property
+    def date(self) -> datetime.date: ...
+    @property
+    def games(self) -> builtins.list[DailyGameStats]: ...
+    @property
+    def note(self) -> typing.Optional[builtins.str]: ...
+
+@typing.final
+class GameStatistics:
+    r"""
+    Totals for one game
+    """
+    @property
+    def game_id(self) -> builtins.str: ...
+    @property
+    def game_name(self) -> builtins.str: ...
+    @property
+    def total_time(self) -> builtins.int: ...
+    @property
+    def total_sessions(self) -> builtins.int: ...
+    @property
+    def last_played(self) -> typing.Optional[datetime.datetime]: ...
+    @property
+    def last_session_duration(self) -> typing.Optional[builtins.int]: ...
+
 @typing.final
 class PlayTime:
     def __new__(cls) -> PlayTime: ...
-    def add_time(self, user_id: builtins.str, data_dir: builtins.str, game_id: builtins.str, game_name: builtins.str, started_at: builtins.float, ended_at: builtins.float) -> None: ...
+    def add_time(self, user_id: builtins.str, data_dir: builtins.str, game_id: builtins.str, game_name: builtins.str, started_at: builtins.float, ended_at: builtins.float) -> builtins.int:
+        r"""
+        Record a play session and return the number of days it was logged across
+        """
+    def get_game_sessions(self, user_id: builtins.str, data_dir: builtins.str, game_id: builtins.str) -> builtins.list[SessionInfo]:
+        r"""
+        Get every recorded session for a game, newest first
+        """
+    def get_total_playtime(self, user_id: builtins.str, data_dir: builtins.str, game_id: builtins.str) -> builtins.int:
+        r"""
+        Get a game's total recorded playtime in seconds
+        """
+    def optimize_database(self, user_id: builtins.str, data_dir: builtins.str) -> builtins.int:
+        r"""
+        Compact a user's database and return the number of bytes reclaimed
+        """
+
+@typing.final
+class SessionInfo:
+    r"""
+    A recorded play session with Unix timestamps in seconds
+    """
+    @property
+    def game_id(self) -> builtins.str: ...
+    @property
+    def started_at(self) -> builtins.float: ...
+    @property
+    def ended_at(self) -> builtins.float: ...
+    @property
+    def duration(self) -> builtins.float: ...
+    @property
+    def checksum(self) -> typing.Optional[builtins.str]: ...
+
+@typing.final
+class Statistics:
+    def __new__(cls) -> Statistics: ...
+    def get_overall(self, user_id: builtins.str, data_dir: builtins.str) -> builtins.list[GameStatistics]:
+        r"""
+        Get totals for every played game, most played first
+        """
+    def get_daily(self, user_id: builtins.str, data_dir: builtins.str, start: builtins.str, end: builtins.str) -> builtins.list[DailyStatistics]:
+        r"""
+        Get per-day statistics between two ISO dates (YYYY-MM-DD), inclusive
+        """
+    def get_for_game(self, user_id: builtins.str, data_dir: builtins.str, game_id: builtins.str) -> typing.Optional[GameStatistics]:
+        r"""
+        Get totals for one game, or None if it was never played
+        """
+    def export_csv(self, user_id: builtins.str, data_dir: builtins.str, out_path: builtins.str) -> None:
+        r"""
+        Write every recorded session to `out_path` as CSV
+        """
 
 @typing.final
 class UserManager:
@@ -20,6 +116,25 @@ class UserManager:
     def get_user_db_path_str(self, user_id: builtins.str) -> builtins.str: ...
     def get_data_dir(self) -> builtins.str: ...
     def clear_current_user(self) -> None: ...
+    def export_user_db(self, user_id: builtins.str, dest_path: builtins.str) -> None:
+        r"""
+        Write a standalone copy of a user's database to `dest_path`
+        """
 
 def clear_db_cache() -> None: ...
 
+def maintenance(db_path: builtins.str) -> None:
+    r"""
+    Vacuum the database at `db_path` and truncate its WAL
+    """
+
+def db_schema_version(db_path: builtins.str) -> builtins.int:
+    r"""
+    Read the schema version of the database at `db_path` without migrating it
+    """
+
+def format_duration(seconds: builtins.int, style: builtins.str) -> builtins.str:
+    r"""
+    Format seconds for display using the "hm", "clock" or "compact" style
+    """
+
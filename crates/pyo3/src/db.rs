@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, LazyLock};
 
 use parking_lot::Mutex;
@@ -14,29 +14,68 @@ pub static DB_CACHE: LazyLock<Mutex<HashMap<String, Arc<Database>>>> =
 /// If the database already exists in the cache, returns the cached instance.
 /// Otherwise, creates a new database, runs migrations, and caches it.
 pub fn get_or_create_database<P: AsRef<Path>>(db_path: P) -> Result<Arc<Database>, CoreError> {
-    let db_path = db_path.as_ref();
+    let db_path = canonicalize_path(db_path.as_ref());
+    let db_path = db_path.as_path();
     let cache_key = db_path.to_string_lossy().to_string();
 
     // Try to get from cache first
     let mut cache = DB_CACHE.lock();
 
     if let Some(db) = cache.get(&cache_key) {
-        println!("[RUST][DB_CACHE] Reusing cached database at {:?}", db_path);
+        log::debug!(target: "playtime::db", "Reusing cached database at {:?}", db_path);
         return Ok(Arc::clone(db));
     }
 
-    // Create new database and run migrations
+    // Create new database, back it up if it is behind, and run migrations
     let db = Database::new(db_path)?;
-    db.with_connection(|conn| playtime_core::db::migrations::run_migrations(conn))?;
+
+    if let Some(backup_path) = db.backup_before_migration()? {
+        log::info!(
+            target: "playtime::db",
+            "Backed up database to {:?} before migrating",
+            backup_path
+        );
+    }
+
+    db.with_connection(playtime_core::db::migrations::run_migrations)?;
 
     let db = Arc::new(db);
     cache.insert(cache_key, Arc::clone(&db));
 
-    println!("[RUST][DB_CACHE] Created new database at {:?}", db_path);
+    log::info!(target: "playtime::db", "Created new database at {:?}", db_path);
 
     Ok(db)
 }
 
+/// Resolve symlinks and relative components so equivalent spellings of a
+/// path map to the same cache key
+///
+/// Parts of the path that don't exist yet are appended unchanged to the
+/// canonical form of the deepest existing ancestor.
+pub fn canonicalize_path(path: &Path) -> PathBuf {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+
+    let mut existing = absolute.as_path();
+    let mut missing = Vec::new();
+
+    loop {
+        if let Ok(canonical) = std::fs::canonicalize(existing) {
+            return missing
+                .iter()
+                .rev()
+                .fold(canonical, |acc: PathBuf, part| acc.join(part));
+        }
+
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => return absolute,
+        }
+    }
+}
+
 /// Clear the database cache (useful for testing)
 pub fn clear_cache() {
     DB_CACHE.lock().clear();
@@ -48,8 +87,12 @@ mod tests {
 
     use super::*;
 
+    /// Tests share the global cache, so they must not clear it under each other
+    static CACHE_TEST_LOCK: Mutex<()> = Mutex::new(());
+
     #[test]
     fn test_cache_reuse() {
+        let _guard = CACHE_TEST_LOCK.lock();
         let temp_dir = env::temp_dir();
         let db_path = temp_dir.join("storage.db");
 
@@ -68,4 +111,38 @@ mod tests {
         clear_cache();
         std::fs::remove_file(db_path).ok();
     }
+
+    #[test]
+    fn test_equivalent_paths_share_cache_entry() {
+        let _guard = CACHE_TEST_LOCK.lock();
+        let data_dir = env::temp_dir().join(format!("test_canonical_{}", std::process::id()));
+        let nested = data_dir.join("users").join("123");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let direct = nested.join("storage.db");
+        let roundabout = data_dir
+            .join("users")
+            .join(".")
+            .join("123")
+            .join("..")
+            .join("123")
+            .join("storage.db");
+
+        let db1 = get_or_create_database(&direct).unwrap();
+        let db2 = get_or_create_database(&roundabout).unwrap();
+        assert!(Arc::ptr_eq(&db1, &db2));
+
+        let not_created = data_dir
+            .join("users")
+            .join("..")
+            .join("new")
+            .join("storage.db");
+        assert_eq!(
+            canonicalize_path(&not_created),
+            canonicalize_path(&data_dir).join("new").join("storage.db")
+        );
+
+        clear_cache();
+        std::fs::remove_dir_all(data_dir).ok();
+    }
 }
@@ -1,11 +1,21 @@
+use std::path::Path;
+
 mod db;
+mod logging;
+mod models;
 mod playtime;
+mod statistics;
 mod user_manager;
 
+pub use models::{PyDailyGameStats, PyDailyStats, PyGameStats, PySession};
 pub use playtime::PlayTime;
+use playtime::to_py_err;
+use playtime_core::db::Database;
+use playtime_core::utils::{DurationStyle, format_duration as format_duration_core};
 use pyo3::prelude::*;
 use pyo3_stub_gen::define_stub_info_gatherer;
 use pyo3_stub_gen::derive::gen_stub_pyfunction;
+pub use statistics::Statistics;
 pub use user_manager::UserManager;
 
 #[gen_stub_pyfunction]
@@ -14,11 +24,59 @@ fn clear_db_cache() {
     db::clear_cache();
 }
 
+/// Vacuum the database at `db_path` and truncate its WAL
+#[gen_stub_pyfunction]
+#[pyfunction]
+fn maintenance(py: Python<'_>, db_path: &str) -> PyResult<()> {
+    let db = db::get_or_create_database(db_path).map_err(to_py_err)?;
+
+    py.detach(|| {
+        db.vacuum()?;
+        db.checkpoint()
+    })
+    .map_err(to_py_err)
+}
+
+/// Read the schema version of the database at `db_path` without migrating it
+#[gen_stub_pyfunction]
+#[pyfunction]
+fn db_schema_version(db_path: &str) -> PyResult<i32> {
+    if !Path::new(db_path).is_file() {
+        return Err(to_py_err(playtime_core::Error::NotFound(format!(
+            "Database not found: {}",
+            db_path
+        ))));
+    }
+
+    Database::new(db_path)
+        .and_then(|db| db.schema_version())
+        .map_err(to_py_err)
+}
+
+/// Format seconds for display using the "hm", "clock" or "compact" style
+#[gen_stub_pyfunction]
+#[pyfunction]
+fn format_duration(seconds: i64, style: &str) -> PyResult<String> {
+    let style: DurationStyle = style.parse().map_err(to_py_err)?;
+
+    Ok(format_duration_core(seconds, style))
+}
+
 #[pymodule]
 fn playtime_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    logging::init(m.py())?;
+
     m.add_class::<PlayTime>()?;
     m.add_class::<UserManager>()?;
+    m.add_class::<PySession>()?;
+    m.add_class::<Statistics>()?;
+    m.add_class::<PyGameStats>()?;
+    m.add_class::<PyDailyStats>()?;
+    m.add_class::<PyDailyGameStats>()?;
     m.add_function(wrap_pyfunction!(clear_db_cache, m)?)?;
+    m.add_function(wrap_pyfunction!(maintenance, m)?)?;
+    m.add_function(wrap_pyfunction!(db_schema_version, m)?)?;
+    m.add_function(wrap_pyfunction!(format_duration, m)?)?;
 
     Ok(())
 }
@@ -0,0 +1,79 @@
+//! Bridge from the `log` facade to Python's `logging` module
+//!
+//! Records are forwarded to `logging.getLogger(target)` with `::` replaced by
+//! `.`, so a `playtime::db` record lands on the `playtime.db` logger and
+//! propagates to whatever handlers the plugin configured.
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use pyo3::prelude::*;
+
+/// Python logger whose effective level decides which records are forwarded
+const ROOT_LOGGER: &str = "playtime";
+
+struct PythonLogger;
+
+static LOGGER: PythonLogger = PythonLogger;
+
+impl Log for PythonLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let name = record.target().replace("::", ".");
+        let message = record.args().to_string();
+        let level = python_level(record.level());
+
+        Python::attach(|py| {
+            // Logging must never raise into the caller
+            let _ = py
+                .import("logging")
+                .and_then(|logging| logging.call_method1("getLogger", (name,)))
+                .and_then(|logger| logger.call_method1("log", (level, message)));
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+/// Install the bridge once, filtering at the `playtime` logger's effective
+/// level at import time
+///
+/// Importing the module again keeps the logger that is already installed.
+pub fn init(py: Python<'_>) -> PyResult<()> {
+    let effective_level: u32 = py
+        .import("logging")?
+        .call_method1("getLogger", (ROOT_LOGGER,))?
+        .call_method0("getEffectiveLevel")?
+        .extract()?;
+
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(level_filter(effective_level));
+    }
+
+    Ok(())
+}
+
+fn python_level(level: Level) -> u32 {
+    match level {
+        Level::Error => 40,
+        Level::Warn => 30,
+        Level::Info => 20,
+        Level::Debug => 10,
+        Level::Trace => 5,
+    }
+}
+
+fn level_filter(python_level: u32) -> LevelFilter {
+    match python_level {
+        0..=5 => LevelFilter::Trace,
+        6..=10 => LevelFilter::Debug,
+        11..=20 => LevelFilter::Info,
+        21..=30 => LevelFilter::Warn,
+        _ => LevelFilter::Error,
+    }
+}
@@ -0,0 +1,199 @@
+//! Python-facing copies of core models
+//!
+//! Fields are read-only on the Python side.
+
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use playtime_core::models::{
+    DailyGameStats, DailyStatistics, GameStatistics, PlaySession, SessionInfo,
+};
+use pyo3::prelude::*;
+use pyo3_stub_gen::derive::gen_stub_pyclass;
+
+/// A recorded play session with Unix timestamps in seconds
+#[gen_stub_pyclass]
+#[pyclass(frozen, get_all, name = "SessionInfo")]
+#[derive(Debug, Clone)]
+pub struct PySession {
+    pub game_id: String,
+    pub started_at: f64,
+    pub ended_at: f64,
+    pub duration: f64,
+    pub checksum: Option<String>,
+}
+
+impl From<PlaySession> for PySession {
+    fn from(session: PlaySession) -> Self {
+        Self {
+            game_id: session.game_id,
+            started_at: session.started_at,
+            ended_at: session.ended_at,
+            duration: session.duration,
+            checksum: session.checksum,
+        }
+    }
+}
+
+impl From<(String, SessionInfo)> for PySession {
+    fn from((game_id, session): (String, SessionInfo)) -> Self {
+        let started_at = session
+            .date
+            .and_local_timezone(Local)
+            .earliest()
+            .map_or(0.0, |dt| dt.timestamp() as f64);
+
+        Self {
+            game_id,
+            started_at,
+            ended_at: started_at + session.duration,
+            duration: session.duration,
+            checksum: session.checksum,
+        }
+    }
+}
+
+/// Totals for one game
+#[gen_stub_pyclass]
+#[pyclass(frozen, get_all, name = "GameStatistics")]
+#[derive(Debug, Clone)]
+pub struct PyGameStats {
+    pub game_id: String,
+    pub game_name: String,
+    pub total_time: i64,
+    pub total_sessions: i64,
+    pub last_played: Option<NaiveDateTime>,
+    pub last_session_duration: Option<i64>,
+}
+
+impl From<GameStatistics> for PyGameStats {
+    fn from(stats: GameStatistics) -> Self {
+        Self {
+            game_id: stats.game.id,
+            game_name: stats.game.name,
+            total_time: stats.total_time,
+            total_sessions: stats.total_sessions,
+            last_played: stats.last_played,
+            last_session_duration: stats.last_session_duration,
+        }
+    }
+}
+
+/// Playtime for one game on one day
+#[gen_stub_pyclass]
+#[pyclass(frozen, get_all, name = "DailyGameStats")]
+#[derive(Debug, Clone)]
+pub struct PyDailyGameStats {
+    pub game_id: String,
+    pub game_name: String,
+    pub time: i64,
+    pub sessions: Vec<PySession>,
+}
+
+impl From<DailyGameStats> for PyDailyGameStats {
+    fn from(stats: DailyGameStats) -> Self {
+        let sessions = stats
+            .sessions
+            .into_iter()
+            .map(|session| PySession::from((stats.game.id.clone(), session)))
+            .collect();
+
+        Self {
+            game_id: stats.game.id,
+            game_name: stats.game.name,
+            time: stats.time,
+            sessions,
+        }
+    }
+}
+
+/// Per-game playtime for one day
+#[gen_stub_pyclass]
+#[pyclass(frozen, get_all, name = "DailyStatistics")]
+#[derive(Debug, Clone)]
+pub struct PyDailyStats {
+    pub date: NaiveDate,
+    pub games: Vec<PyDailyGameStats>,
+    pub note: Option<String>,
+}
+
+impl From<DailyStatistics> for PyDailyStats {
+    fn from(stats: DailyStatistics) -> Self {
+        Self {
+            date: stats.date,
+            games: stats
+                .games
+                .into_iter()
+                .map(PyDailyGameStats::from)
+                .collect(),
+            note: stats.note,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone};
+    use playtime_core::models::Game;
+
+    use super::*;
+
+    #[test]
+    fn test_daily_stats_from_core() {
+        let started = NaiveDate::from_ymd_opt(2024, 3, 1)
+            .and_then(|d| d.and_hms_opt(10, 0, 0))
+            .unwrap();
+        let core = DailyStatistics {
+            date: started.date(),
+            games: vec![DailyGameStats {
+                game: Game::new("123", "Test Game"),
+                time: 600,
+                sessions: vec![SessionInfo {
+                    date: started,
+                    duration: 600.0,
+                    migrated: None,
+                    checksum: Some("abc".into()),
+                }],
+            }],
+            note: Some("rainy day".into()),
+        };
+
+        let dto = PyDailyStats::from(core);
+        assert_eq!(dto.date, started.date());
+        assert_eq!(dto.note.as_deref(), Some("rainy day"));
+
+        let game = &dto.games[0];
+        assert_eq!(
+            (game.game_id.as_str(), game.game_name.as_str()),
+            ("123", "Test Game")
+        );
+        assert_eq!(game.time, 600);
+
+        let session = &game.sessions[0];
+        let expected_start = Local.from_local_datetime(&started).unwrap().timestamp() as f64;
+        assert_eq!(session.game_id, "123");
+        assert_eq!(session.started_at, expected_start);
+        assert_eq!(session.ended_at, expected_start + 600.0);
+        assert_eq!(session.checksum.as_deref(), Some("abc"));
+    }
+
+    #[test]
+    fn test_game_stats_from_core() {
+        let last_played = NaiveDate::from_ymd_opt(2024, 3, 1)
+            .and_then(|d| d.and_hms_opt(10, 0, 0))
+            .unwrap();
+        let core = GameStatistics {
+            game: Game::new("123", "Test Game"),
+            total_time: 3600,
+            total_sessions: 4,
+            last_played: Some(last_played),
+            last_session_duration: Some(900),
+        };
+
+        let dto = PyGameStats::from(core);
+        assert_eq!(dto.game_id, "123");
+        assert_eq!(dto.game_name, "Test Game");
+        assert_eq!(dto.total_time, 3600);
+        assert_eq!(dto.total_sessions, 4);
+        assert_eq!(dto.last_played, Some(last_played));
+        assert_eq!(dto.last_session_duration, Some(900));
+    }
+}
@@ -15,9 +15,10 @@ use pyo3_stub_gen::define_stub_info_gatherer;
 use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pyfunction, gen_stub_pymethods};
 
 use crate::db::get_or_create_database;
+use crate::models::PySession;
 
 /// Convert core errors to Python exceptions
-fn to_py_err(err: CoreError) -> PyErr {
+pub(crate) fn to_py_err(err: CoreError) -> PyErr {
     PyException::new_err(err.to_string())
 }
 
@@ -33,6 +34,7 @@ impl PlayTime {
         Ok(Self {})
     }
 
+    /// Record a play session and return the number of days it was logged across
     fn add_time(
         &self,
         user_id: &str,
@@ -41,25 +43,64 @@ impl PlayTime {
         game_name: &str,
         started_at: f64,
         ended_at: f64,
-    ) -> PyResult<()> {
+    ) -> PyResult<usize> {
+        if !started_at.is_finite() || !ended_at.is_finite() {
+            return Err(to_py_err(CoreError::InvalidInput(
+                "Start and end times must be finite".into(),
+            )));
+        }
+
         let db = Self::get_database(user_id, data_dir).map_err(to_py_err)?;
         let service = TimeTrackingService::new(db);
 
-        println!(
-            "[RUST][add_time] user_id: {}, game_id: {}, started_at: {}, ended_at: {}",
+        log::debug!(
+            target: "playtime::time_tracking",
+            "add_time user_id: {}, game_id: {}, started_at: {}, ended_at: {}",
             user_id, game_id, started_at, ended_at
         );
 
         service
-            .add_time(game_id, game_name, started_at, ended_at, None)
+            .add_time(game_id, game_name, started_at, ended_at, None, None)
             .map_err(to_py_err)
     }
+
+    /// Get every recorded session for a game, newest first
+    fn get_game_sessions(
+        &self,
+        user_id: &str,
+        data_dir: &str,
+        game_id: &str,
+    ) -> PyResult<Vec<PySession>> {
+        let db = Self::get_database(user_id, data_dir).map_err(to_py_err)?;
+
+        let sessions = TimeTrackingService::new(db)
+            .get_game_sessions(game_id)
+            .map_err(to_py_err)?;
+
+        Ok(sessions.into_iter().map(PySession::from).collect())
+    }
+
+    /// Get a game's total recorded playtime in seconds
+    fn get_total_playtime(&self, user_id: &str, data_dir: &str, game_id: &str) -> PyResult<i64> {
+        let db = Self::get_database(user_id, data_dir).map_err(to_py_err)?;
+
+        TimeTrackingService::new(db)
+            .get_total_playtime(game_id)
+            .map_err(to_py_err)
+    }
+
+    /// Compact a user's database and return the number of bytes reclaimed
+    fn optimize_database(&self, py: Python<'_>, user_id: &str, data_dir: &str) -> PyResult<u64> {
+        let db = Self::get_database(user_id, data_dir).map_err(to_py_err)?;
+
+        py.detach(|| db.optimize()).map_err(to_py_err)
+    }
 }
 
 impl PlayTime {
     /// Get database connection for a user (cached)
     pub fn get_database(user_id: &str, data_dir: &str) -> Result<Arc<Database>, CoreError> {
-        println!("[RUST][get_database] {} | {}", user_id, data_dir);
+        log::debug!(target: "playtime::db", "get_database {} | {}", user_id, data_dir);
 
         let db_path = PathBuf::from(data_dir)
             .join("users")
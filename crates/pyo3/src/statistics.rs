@@ -0,0 +1,104 @@
+//! Statistics - Read-only PyO3 class for playtime reports
+//!
+//! Stateless like `PlayTime`: every call takes user_id and data_dir and goes
+//! through the global DB_CACHE.
+
+use std::fs::File;
+use std::io::BufWriter;
+
+use chrono::NaiveDate;
+use playtime_core::domain::StatisticsService;
+use playtime_core::error::Error as CoreError;
+use pyo3::prelude::*;
+use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pymethods};
+
+use crate::models::{PyDailyStats, PyGameStats};
+use crate::playtime::{PlayTime, to_py_err};
+
+#[gen_stub_pyclass]
+#[pyclass]
+pub struct Statistics {}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl Statistics {
+    #[new]
+    fn new() -> PyResult<Self> {
+        Ok(Self {})
+    }
+
+    /// Get totals for every played game, most played first
+    fn get_overall(&self, user_id: &str, data_dir: &str) -> PyResult<Vec<PyGameStats>> {
+        let stats = Self::service(user_id, data_dir)?
+            .get_overall()
+            .map_err(to_py_err)?;
+
+        Ok(stats.into_iter().map(PyGameStats::from).collect())
+    }
+
+    /// Get per-day statistics between two ISO dates (YYYY-MM-DD), inclusive
+    fn get_daily(
+        &self,
+        user_id: &str,
+        data_dir: &str,
+        start: &str,
+        end: &str,
+    ) -> PyResult<Vec<PyDailyStats>> {
+        let start = parse_date(start)?;
+        let end = parse_date(end)?;
+
+        let stats = Self::service(user_id, data_dir)?
+            .get_daily(start, end)
+            .map_err(to_py_err)?;
+
+        Ok(stats.into_iter().map(PyDailyStats::from).collect())
+    }
+
+    /// Get totals for one game, or None if it was never played
+    fn get_for_game(
+        &self,
+        user_id: &str,
+        data_dir: &str,
+        game_id: &str,
+    ) -> PyResult<Option<PyGameStats>> {
+        let stats = Self::service(user_id, data_dir)?
+            .get_for_game(game_id)
+            .map_err(to_py_err)?;
+
+        Ok(stats.map(PyGameStats::from))
+    }
+
+    /// Write every recorded session to `out_path` as CSV
+    fn export_csv(
+        &self,
+        py: Python<'_>,
+        user_id: &str,
+        data_dir: &str,
+        out_path: &str,
+    ) -> PyResult<()> {
+        let service = Self::service(user_id, data_dir)?;
+
+        py.detach(|| {
+            let file = File::create(out_path)?;
+            service.export_sessions_csv(BufWriter::new(file))
+        })
+        .map_err(to_py_err)
+    }
+}
+
+impl Statistics {
+    fn service(user_id: &str, data_dir: &str) -> PyResult<StatisticsService> {
+        let db = PlayTime::get_database(user_id, data_dir).map_err(to_py_err)?;
+
+        Ok(StatisticsService::new(db))
+    }
+}
+
+fn parse_date(date: &str) -> PyResult<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| {
+        to_py_err(CoreError::InvalidInput(format!(
+            "Invalid date {:?}, expected YYYY-MM-DD",
+            date
+        )))
+    })
+}
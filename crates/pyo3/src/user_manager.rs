@@ -7,7 +7,7 @@ use pyo3::exceptions::PyException;
 use pyo3::prelude::*;
 use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pymethods};
 
-use crate::db::get_or_create_database;
+use crate::db::{canonicalize_path, get_or_create_database};
 
 const USERS_SUBDIR: &str = "users";
 const STORAGE_DB_FILENAME: &str = "storage.db";
@@ -31,6 +31,8 @@ impl UserManager {
                 .map_err(|e| PyException::new_err(format!("Failed to create data_dir: {}", e)))?;
         }
 
+        let data_dir = canonicalize_path(&data_dir);
+
         Ok(Self {
             data_dir,
             current_user_id: Arc::new(Mutex::new(None)),
@@ -129,6 +131,30 @@ impl UserManager {
     fn clear_current_user(&self) {
         *self.current_user_id.lock() = None;
     }
+
+    /// Write a standalone copy of a user's database to `dest_path`
+    fn export_user_db(&self, user_id: String, dest_path: String) -> PyResult<()> {
+        if !self.has_user_db(&user_id) {
+            return Err(PyException::new_err(format!(
+                "No database for user {}",
+                user_id
+            )));
+        }
+
+        let db = get_or_create_database(self.get_user_db_path(&user_id)).map_err(|e| {
+            PyException::new_err(format!(
+                "Failed to open database for user {}: {}",
+                user_id, e
+            ))
+        })?;
+
+        db.backup_to(&dest_path).map_err(|e| {
+            PyException::new_err(format!(
+                "Failed to export database for user {}: {}",
+                user_id, e
+            ))
+        })
+    }
 }
 
 impl UserManager {
@@ -162,8 +188,9 @@ impl UserManager {
         let legacy_size = fs::metadata(&legacy_path).map(|m| m.len()).unwrap_or(0);
         let legacy_size_mb = legacy_size as f64 / (1024.0 * 1024.0);
 
-        println!(
-            "[UserManager] Migrating legacy DB for user {}: {} -> {} (size: {:.2} MB)",
+        log::info!(
+            target: "playtime::users",
+            "Migrating legacy DB for user {}: {} -> {} (size: {:.2} MB)",
             user_id,
             legacy_path.display(),
             user_db_path.display(),
@@ -177,8 +204,9 @@ impl UserManager {
             ))
         })?;
 
-        println!(
-            "[UserManager] Successfully migrated legacy DB for user: {} ({:.2} MB copied)",
+        log::info!(
+            target: "playtime::users",
+            "Successfully migrated legacy DB for user: {} ({:.2} MB copied)",
             user_id, legacy_size_mb
         );
 
@@ -1,5 +1,7 @@
 import sys
 import shutil
+import sqlite3
+import tempfile
 from pathlib import Path
 
 # Add the release folder to Python path to import the compiled library
@@ -42,6 +44,197 @@ def test_basic_functionality():
     print("✓ clear_db_cache function available")
 
 
+def test_format_duration():
+    """Test duration formatting shared with the frontend"""
+
+    assert playtime_rs.format_duration(9000, "hm") == "2h 30m"
+    assert playtime_rs.format_duration(9000, "clock") == "02:30:00"
+    assert playtime_rs.format_duration(9000, "compact") == "2h30m"
+
+    try:
+        playtime_rs.format_duration(9000, "days")
+    except Exception:
+        pass
+    else:
+        raise AssertionError("Unknown style should raise")
+
+    print("✓ format_duration works")
+
+
+def test_add_time_rejects_nan():
+    """Test that non-finite timestamps are rejected before touching the DB"""
+
+    with tempfile.TemporaryDirectory() as data_dir:
+        try:
+            playtime_rs.PlayTime().add_time(
+                "user", data_dir, "123", "Test Game", float("nan"), 1000.0
+            )
+        except Exception:
+            pass
+        else:
+            raise AssertionError("NaN start time should raise")
+
+        assert not (Path(data_dir) / "users").exists()
+
+    print("✓ add_time rejects non-finite timestamps")
+
+
+def test_export_user_db():
+    """Test exporting a user's database as a standalone SQLite file"""
+
+    with tempfile.TemporaryDirectory() as data_dir:
+        manager = playtime_rs.UserManager(data_dir)
+        manager.set_current_user("76561198000000000")
+        playtime_rs.PlayTime().add_time(
+            "76561198000000000", data_dir, "123", "Test Game", 1709294400.0, 1709295000.0
+        )
+
+        dest = Path(data_dir) / "export.db"
+        manager.export_user_db("76561198000000000", str(dest))
+
+        with sqlite3.connect(dest) as conn:
+            rows = conn.execute("SELECT game_id FROM play_time").fetchall()
+        assert rows == [("123",)]
+
+        try:
+            manager.export_user_db("1", str(Path(data_dir) / "missing.db"))
+        except Exception:
+            pass
+        else:
+            raise AssertionError("Unknown user should raise")
+
+    playtime_rs.clear_db_cache()
+    print("✓ export_user_db works")
+
+
+def test_read_sessions_back():
+    """Test reading recorded sessions and totals through PlayTime"""
+
+    with tempfile.TemporaryDirectory() as data_dir:
+        playtime = playtime_rs.PlayTime()
+        assert (
+            playtime.add_time(
+                "user", data_dir, "123", "Test Game", 1709294400.0, 1709295000.0
+            )
+            == 1
+        )
+
+        sessions = playtime.get_game_sessions("user", data_dir, "123")
+        assert len(sessions) == 1
+        assert sessions[0].game_id == "123"
+        assert sessions[0].duration == 600.0
+        assert playtime.get_total_playtime("user", data_dir, "123") == 600
+        assert playtime.get_game_sessions("user", data_dir, "456") == []
+
+    playtime_rs.clear_db_cache()
+    print("✓ get_game_sessions and get_total_playtime work")
+
+
+def test_optimize_database():
+    """Test compacting a populated user database"""
+
+    with tempfile.TemporaryDirectory() as data_dir:
+        playtime = playtime_rs.PlayTime()
+        playtime.add_time(
+            "user", data_dir, "123", "Test Game", 1709294400.0, 1709295000.0
+        )
+
+        assert playtime.optimize_database("user", data_dir) >= 0
+
+    playtime_rs.clear_db_cache()
+    print("✓ optimize_database works")
+
+
+def test_statistics():
+    """Test reading overall, daily and per-game statistics"""
+
+    with tempfile.TemporaryDirectory() as data_dir:
+        playtime_rs.PlayTime().add_time(
+            "user", data_dir, "123", "Test Game", 1709294400.0, 1709295000.0
+        )
+        statistics = playtime_rs.Statistics()
+
+        overall = statistics.get_overall("user", data_dir)
+        assert len(overall) == 1
+        assert overall[0].game_name == "Test Game"
+        assert overall[0].total_time == 600
+
+        daily = statistics.get_daily("user", data_dir, "2024-03-01", "2024-03-02")
+        assert sum(game.time for day in daily for game in day.games) == 600
+
+        assert statistics.get_for_game("user", data_dir, "123").total_sessions == 1
+        assert statistics.get_for_game("user", data_dir, "456") is None
+
+        try:
+            statistics.get_daily("user", data_dir, "03/01/2024", "2024-03-02")
+        except Exception:
+            pass
+        else:
+            raise AssertionError("Malformed date should raise")
+
+    playtime_rs.clear_db_cache()
+    print("✓ Statistics works")
+
+
+def test_export_csv():
+    """Test exporting recorded sessions as CSV"""
+
+    with tempfile.TemporaryDirectory() as data_dir:
+        playtime_rs.PlayTime().add_time(
+            "user", data_dir, "123", "Test Game", 1709294400.0, 1709295000.0
+        )
+
+        out_path = Path(data_dir) / "sessions.csv"
+        playtime_rs.Statistics().export_csv("user", data_dir, str(out_path))
+
+        lines = out_path.read_text().splitlines()
+        assert lines[0] == "game_id,game_name,date_time,duration,migrated"
+        assert len(lines) == 2
+        assert lines[1].startswith("123,Test Game,")
+
+    playtime_rs.clear_db_cache()
+    print("✓ export_csv works")
+
+
+def test_db_schema_version():
+    """Test reading a database's schema version by path"""
+
+    with tempfile.TemporaryDirectory() as data_dir:
+        playtime_rs.PlayTime().add_time(
+            "user", data_dir, "123", "Test Game", 1709294400.0, 1709295000.0
+        )
+
+        db_path = Path(data_dir) / "users" / "user" / "storage.db"
+        assert playtime_rs.db_schema_version(str(db_path)) > 0
+
+        try:
+            playtime_rs.db_schema_version(str(Path(data_dir) / "missing.db"))
+        except Exception:
+            pass
+        else:
+            raise AssertionError("Missing database should raise")
+
+    playtime_rs.clear_db_cache()
+    print("✓ db_schema_version works")
+
+
+def test_maintenance():
+    """Test vacuuming and checkpointing a database by path"""
+
+    with tempfile.TemporaryDirectory() as data_dir:
+        playtime_rs.PlayTime().add_time(
+            "user", data_dir, "123", "Test Game", 1709294400.0, 1709295000.0
+        )
+
+        db_path = Path(data_dir) / "users" / "user" / "storage.db"
+        playtime_rs.maintenance(str(db_path))
+
+        assert playtime_rs.PlayTime().get_total_playtime("user", data_dir, "123") == 600
+
+    playtime_rs.clear_db_cache()
+    print("✓ maintenance works")
+
+
 if __name__ == "__main__":
     print(f"Python path: {sys.path}")
     print(f"Looking for library in: {release_lib_path}")
@@ -50,4 +243,13 @@ if __name__ == "__main__":
     test_import()
     print("\nRunning tests...")
     test_basic_functionality()
+    test_format_duration()
+    test_add_time_rejects_nan()
+    test_export_user_db()
+    test_read_sessions_back()
+    test_optimize_database()
+    test_statistics()
+    test_export_csv()
+    test_db_schema_version()
+    test_maintenance()
     print("\n✓ All tests passed!")